@@ -1,7 +1,11 @@
 use ansi_term::Colour;
 
+use crate::board::heuristic::LineCounting;
+use crate::game::game::{Evaluator, Game, GameSymmetry, Outcome, Player};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+// Ordered row-major, by row and then by column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Move {
     pub row: usize,
     pub col: usize,
@@ -49,6 +53,11 @@ impl Move {
         self.col
     }
 
+    pub fn transformed(&self, symmetry: &Symmetry) -> Move {
+        let (row, col) = symmetry.apply(self.row, self.col);
+        Move::new(row, col)
+    }
+
     pub fn to_string(&self) -> String {
         format!("({}, {})", self.row, self.col)
     }
@@ -77,6 +86,34 @@ impl Move {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Square {
+    Corner,
+    Edge,
+    Centre,
+}
+
+impl Square {
+
+    pub fn of(m: Move) -> Square {
+        match (m.get_row(), m.get_column()) {
+            (1, 1) => Square::Centre,
+            (1, _) | (_, 1) => Square::Edge,
+            _ => Square::Corner,
+        }
+    }
+
+    pub fn from_string(s: &str) -> Result<Square, String> {
+        match s {
+            "corner" => Ok(Square::Corner),
+            "edge" => Ok(Square::Edge),
+            "centre" | "center" => Ok(Square::Centre),
+            _ => Err(format!("Invalid square kind: {}", s)),
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Board {
     x_bitboard: Bitboard,
     o_bitboard: Bitboard,
@@ -227,12 +264,237 @@ impl Board {
         }
         moves
     }
+
+    // How many of `player`'s pieces are on each row, column and diagonal the opponent hasn't blocked yet.
+    pub fn get_open_lines(&self, player: Player) -> Vec<usize> {
+        let (own, opponent) = match player {
+            Player::X => (self.x_bitboard, self.o_bitboard),
+            Player::O => (self.o_bitboard, self.x_bitboard),
+        };
+        LINES.iter()
+            .filter(|line| opponent.intersection(line).n_set() == 0)
+            .map(|line| own.intersection(line).n_set())
+            .collect()
+    }
+
+    // Empty squares that would complete a line for `player`.
+    pub fn get_winning_moves(&self, player: Player) -> Vec<Move> {
+        self.get_legal_moves().into_iter()
+            .filter(|m| {
+                let mut bitboard = match player {
+                    Player::X => self.x_bitboard,
+                    Player::O => self.o_bitboard,
+                };
+                bitboard.set(m.get_row(), m.get_column());
+                bitboard.is_victory()
+            })
+            .collect()
+    }
+
+    // Empty squares that would give `player` two squares to win on at once, more than the opponent can block. A square
+    // that completes a line is a win rather than a fork, even though every square after it would "win" too.
+    pub fn get_fork_moves(&self, player: Player) -> Vec<Move> {
+        let winning_moves = self.get_winning_moves(player);
+        self.get_legal_moves().into_iter()
+            .filter(|m| !winning_moves.contains(m))
+            .filter(|m| {
+                let mut board = *self;
+                match player {
+                    Player::X => board.x_bitboard.set(m.get_row(), m.get_column()),
+                    Player::O => board.o_bitboard.set(m.get_row(), m.get_column()),
+                }
+                board.get_winning_moves(player).len() >= 2
+            })
+            .collect()
+    }
+
+    pub fn transformed(&self, symmetry: &Symmetry) -> Board {
+        let mut x_bitboard = Bitboard::empty();
+        let mut o_bitboard = Bitboard::empty();
+        for row in 0..3 {
+            for col in 0..3 {
+                let (new_row, new_col) = symmetry.apply(row, col);
+                if self.x_bitboard.is_set(row, col) {
+                    x_bitboard.set(new_row, new_col);
+                }
+                if self.o_bitboard.is_set(row, col) {
+                    o_bitboard.set(new_row, new_col);
+                }
+            }
+        }
+        Board::new(x_bitboard, o_bitboard)
+    }
+
+    // Returns the representative of this board's symmetry class, along with the symmetry that maps this board onto it.
+    pub fn canonical(&self) -> (Board, Symmetry) {
+        Symmetry::all().iter()
+            .map(|symmetry| (self.transformed(symmetry), *symmetry))
+            .min_by_key(|(board, _)| board.key())
+            .expect("There is always at least the identity symmetry.")
+    }
+
+    pub fn key(&self) -> u32 {
+        ((self.x_bitboard.0 as u32) << 9) | (self.o_bitboard.0 as u32)
+    }
     
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+// One of the 8 symmetries of the square, stored as the image of each square (indexed by row * 3 + col).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetry([usize; 9]);
+
+impl Symmetry {
+
+    pub fn identity() -> Self {
+        Symmetry([0, 1, 2, 3, 4, 5, 6, 7, 8])
+    }
+
+    pub fn all() -> Vec<Self> {
+        let rotation = Symmetry::from_fn(|row, col| (col, 2 - row));
+        let reflection = Symmetry::from_fn(|row, col| (row, 2 - col));
+        let mut symmetries = Vec::new();
+        let mut rotated = Symmetry::identity();
+        for _ in 0..4 {
+            symmetries.push(rotated);
+            symmetries.push(reflection.compose(&rotated));
+            rotated = rotation.compose(&rotated);
+        }
+        symmetries
+    }
+
+    fn from_fn(f: impl Fn(usize, usize) -> (usize, usize)) -> Self {
+        let mut squares = [0; 9];
+        for row in 0..3 {
+            for col in 0..3 {
+                let (new_row, new_col) = f(row, col);
+                squares[row * 3 + col] = new_row * 3 + new_col;
+            }
+        }
+        Symmetry(squares)
+    }
+
+    pub fn apply(&self, row: usize, col: usize) -> (usize, usize) {
+        let square = self.0[row * 3 + col];
+        (square / 3, square % 3)
+    }
+
+    // The symmetry that applies `other` first and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut squares = [0; 9];
+        for square in 0..9 {
+            squares[square] = self.0[other.0[square]];
+        }
+        Symmetry(squares)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut squares = [0; 9];
+        for square in 0..9 {
+            squares[self.0[square]] = square;
+        }
+        Symmetry(squares)
+    }
+
+}
+
+impl Game for Board {
+    type Move = Move;
+    type Symmetry = Symmetry;
+
+    fn get_legal_moves(&self) -> Vec<Move> {
+        Board::get_legal_moves(self)
+    }
+
+    fn apply(&self, m: Move) -> Result<Board, String> {
+        let player = self.get_active_player().ok_or("Game is already over".to_string())?;
+        self.with_move_made(player, m)
+    }
+
+    fn get_outcome(&self) -> Outcome {
+        Board::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        Board::get_active_player(self)
+    }
+
+    fn canonical(&self) -> (Board, Symmetry) {
+        Board::canonical(self)
+    }
+
+    fn to_string(&self) -> String {
+        Board::to_string(self)
+    }
+
+    fn default_evaluator() -> Box<dyn Evaluator<Board>> {
+        Box::new(LineCounting::default())
+    }
+
+    // Immediate wins, then blocks of the opponent's wins, then the centre, the corners and finally the edges.
+    fn order_moves(&self, moves: &mut Vec<Move>) {
+        let player = match self.get_active_player() {
+            Some(player) => player,
+            None => return,
+        };
+        let wins = self.get_winning_moves(player);
+        let blocks = self.get_winning_moves(player.get_opponent());
+        moves.sort_by_key(|m| {
+            if wins.contains(m) {
+                0
+            } else if blocks.contains(m) {
+                1
+            } else {
+                match Square::of(*m) {
+                    Square::Centre => 2,
+                    Square::Corner => 3,
+                    Square::Edge => 4,
+                }
+            }
+        });
+    }
+}
+
+impl GameSymmetry<Board> for Symmetry {
+    fn identity() -> Self {
+        Symmetry::identity()
+    }
+
+    fn all() -> Vec<Self> {
+        Symmetry::all()
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Symmetry::compose(self, other)
+    }
+
+    fn inverse(&self) -> Self {
+        Symmetry::inverse(self)
+    }
+
+    fn transform(&self, position: &Board) -> Board {
+        position.transformed(self)
+    }
+
+    fn transform_move(&self, m: Move) -> Move {
+        m.transformed(self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Bitboard(u16);
 
+// The rows, columns and diagonals, in the order `Bitboard::is_victory` checks them.
+const LINES: [Bitboard; 8] = [
+    Bitboard(0b111000000),
+    Bitboard(0b000111000),
+    Bitboard(0b000000111),
+    Bitboard(0b100100100),
+    Bitboard(0b010010010),
+    Bitboard(0b001001001),
+    Bitboard(0b100010001),
+    Bitboard(0b001010100),
+];
+
 impl Bitboard {
 
     pub fn empty() -> Self {
@@ -314,42 +576,6 @@ impl Bitboard {
 }
 
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Outcome {
-    Victory(Player),
-    Draw,
-    InProgress,
-    Ambiguous,
-}
-
-impl Outcome {
-
-    pub fn to_string(&self) -> String {
-        match self {
-            Outcome::Victory(player) => format!("{} wins", player.to_string()),
-            Outcome::Draw => "Draw".to_string(),
-            Outcome::InProgress => "Game in progress".to_string(),
-            Outcome::Ambiguous => "Ambiguous".to_string(),
-        }
-    }
-}
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Player {
-    X,
-    O,
-}
-
-impl Player {
-
-    pub fn to_string(&self) -> String {
-        match self {
-            Player::X => "X".to_string(),
-            Player::O => "O".to_string(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod test_board_tests {
     use super::*;
@@ -530,6 +756,44 @@ mod test_board_tests {
         assert_eq!(board.get_legal_moves(), vec![Move::new(1, 0), Move::new(1, 2), Move::new(2, 0), Move::new(2, 1)]);
     }
 
+    #[test]
+    fn test_board_gets_winning_moves() {
+        let board = Board::from_position(
+            "X_X
+            _O_
+            X_O",
+        ).unwrap();
+        assert_eq!(board.get_winning_moves(Player::X), vec![Move::new(0, 1), Move::new(1, 0)]);
+        assert_eq!(board.get_winning_moves(Player::O), vec![]);
+
+        let board = Board::from_position(
+            "XO_
+            _O_
+            X__",
+        ).unwrap();
+        assert_eq!(board.get_winning_moves(Player::X), vec![Move::new(1, 0)]);
+        assert_eq!(board.get_winning_moves(Player::O), vec![Move::new(2, 1)]);
+    }
+
+    #[test]
+    fn test_board_gets_fork_moves() {
+        let board = Board::from_position(
+            "X__
+            _O_
+            __X",
+        ).unwrap();
+        assert_eq!(board.get_fork_moves(Player::X), vec![Move::new(0, 2), Move::new(2, 0)]);
+        assert_eq!(board.get_fork_moves(Player::O), vec![]);
+        assert_eq!(Board::empty().get_fork_moves(Player::X), vec![]);
+
+        let board = Board::from_position(
+            "X_O
+            _O_
+            __X",
+        ).unwrap();
+        assert_eq!(board.get_fork_moves(Player::O), vec![Move::new(0, 1), Move::new(1, 0), Move::new(1, 2), Move::new(2, 1)]);
+    }
+
     #[test]
     fn test_move_instantiates() {
         let m = Move::from_string("1 2").unwrap();
@@ -548,5 +812,46 @@ mod test_board_tests {
         );
     }
 
+    #[test]
+    fn test_board_symmetries() {
+        assert_eq!(Symmetry::all().len(), 8);
+        for symmetry in Symmetry::all() {
+            assert_eq!(symmetry.compose(&symmetry.inverse()), Symmetry::identity());
+        }
+
+        let board = Board::from_position(
+            "X__
+            _O_
+            ___",
+        ).unwrap();
+        let (canonical, symmetry) = board.canonical();
+        assert_eq!(board.transformed(&symmetry), canonical);
+        for other in [
+            "__X
+            _O_
+            ___",
+            "___
+            _O_
+            __X",
+            "___
+            _O_
+            X__",
+        ] {
+            assert_eq!(Board::from_position(other).unwrap().canonical().0, canonical);
+        }
+        assert_ne!(
+            Board::from_position(
+                "_X_
+                _O_
+                ___",
+            ).unwrap().canonical().0,
+            canonical
+        );
+
+        let m = Move::new(0, 1);
+        let rotation = Symmetry::all()[2];
+        assert_eq!(m.transformed(&rotation).transformed(&rotation.inverse()), m);
+    }
+
 
 }
\ No newline at end of file
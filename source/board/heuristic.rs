@@ -0,0 +1,63 @@
+use crate::board::board::Board;
+use crate::game::game::{Evaluator, Player};
+
+// Counts the lines each side can still complete, weighting them by how close they are: an open line with one
+// piece on it, two in a row, and a fork of two winning squares at once.
+pub struct LineCounting {
+    pub open_line: i32,
+    pub two_in_a_row: i32,
+    pub fork: i32,
+}
+
+impl LineCounting {
+
+    fn score_for(&self, board: &Board, player: Player) -> i32 {
+        let lines = board.get_open_lines(player);
+        let open_lines = lines.iter().filter(|pieces| **pieces == 1).count() as i32;
+        let two_in_a_rows = lines.iter().filter(|pieces| **pieces == 2).count() as i32;
+        let fork = match board.get_winning_moves(player).len() >= 2 {
+            true => self.fork,
+            false => 0,
+        };
+        open_lines * self.open_line + two_in_a_rows * self.two_in_a_row + fork
+    }
+
+}
+
+impl Default for LineCounting {
+    fn default() -> Self {
+        LineCounting { open_line: 1, two_in_a_row: 10, fork: 100 }
+    }
+}
+
+impl Evaluator<Board> for LineCounting {
+    fn evaluate(&self, board: &Board) -> i32 {
+        self.score_for(board, Player::X) - self.score_for(board, Player::O)
+    }
+}
+
+#[cfg(test)]
+mod test_heuristic {
+    use super::*;
+    use crate::game::game::Flat;
+
+    #[test]
+    fn test_line_counting() {
+        let evaluator = LineCounting::default();
+        assert_eq!(evaluator.evaluate(&Board::empty()), 0);
+        // The centre is on four lines, a corner on three and an edge on two.
+        assert_eq!(evaluator.evaluate(&Board::from_position("____X____").unwrap()), 4);
+        assert_eq!(evaluator.evaluate(&Board::from_position("X________").unwrap()), 3);
+        assert_eq!(evaluator.evaluate(&Board::from_position("_X_______").unwrap()), 2);
+        assert_eq!(evaluator.evaluate(&Board::from_position("X___O____").unwrap()), -1);
+
+        let fork = Board::from_position(
+            "X_X
+            _O_
+            X_O"
+        ).unwrap();
+        assert_eq!(fork.get_winning_moves(Player::X).len(), 2);
+        assert!(evaluator.evaluate(&fork) > evaluator.two_in_a_row + evaluator.fork);
+        assert_eq!(Flat.evaluate(&fork), 0);
+    }
+}
\ No newline at end of file
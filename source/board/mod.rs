@@ -1 +1,3 @@
-pub mod board;
\ No newline at end of file
+pub mod board;
+pub mod heuristic;
+pub mod tactics;
\ No newline at end of file
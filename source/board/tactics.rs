@@ -0,0 +1,127 @@
+use std::fmt;
+
+use crate::board::board::{Board, Move};
+
+// A reason a move is good that can be seen on the board without searching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tactic {
+    // Completes a line.
+    Win,
+    // Takes the square the opponent would win on next.
+    Block,
+    // Leaves one square to win on, so the opponent has to block it.
+    Threat,
+    // A double threat: two squares to win on, and the opponent can only block one.
+    Fork,
+    // The opponent had a square that would fork, and either it's taken or they're kept busy blocking elsewhere.
+    StopsFork,
+}
+
+impl fmt::Display for Tactic {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Tactic::Win => "win",
+            Tactic::Block => "block",
+            Tactic::Threat => "threat",
+            Tactic::Fork => "fork",
+            Tactic::StopsFork => "stops fork",
+        })
+    }
+
+}
+
+pub fn tactics_to_string(tactics: &[Tactic]) -> String {
+    tactics.iter().map(|tactic| tactic.to_string()).collect::<Vec<String>>().join(", ")
+}
+
+// The tactics of the player to move playing `m`. A winning move is only tagged as a win, since nothing else matters.
+pub fn get_tactics(board: &Board, m: Move) -> Result<Vec<Tactic>, String> {
+    let player = board.get_active_player().ok_or("The game is already over.".to_string())?;
+    let opponent = player.get_opponent();
+    let after = board.with_move_made(player, m)?;
+    if board.get_winning_moves(player).contains(&m) {
+        return Ok(vec![Tactic::Win]);
+    }
+
+    let mut tactics = Vec::new();
+    if board.get_winning_moves(opponent).contains(&m) {
+        tactics.push(Tactic::Block);
+    }
+    let threats = after.get_winning_moves(player);
+    match threats.len() {
+        0 => (),
+        1 => tactics.push(Tactic::Threat),
+        _ => tactics.push(Tactic::Fork),
+    }
+    // A fork doesn't matter while the opponent can simply win. Otherwise a single threat decides their next move,
+    // which stops their fork as long as the square they're forced onto doesn't fork. Without one, the move has to take
+    // the square they'd fork on, and leave them no other.
+    let forks = board.get_fork_moves(opponent);
+    let stops_fork = !forks.is_empty()
+        && after.get_winning_moves(opponent).is_empty()
+        && match threats[..] {
+            [block] => !after.get_fork_moves(opponent).contains(&block),
+            _ => forks.contains(&m) && after.get_fork_moves(opponent).is_empty(),
+        };
+    if stops_fork {
+        tactics.push(Tactic::StopsFork);
+    }
+    Ok(tactics)
+}
+
+#[cfg(test)]
+mod test_tactics {
+    use super::*;
+    use crate::game::game::Player;
+
+    #[test]
+    fn test_tactics_of_wins_blocks_and_forks() {
+        let board = Board::from_position(
+            "XX_
+            OO_
+            ___"
+        ).unwrap();
+        assert_eq!(get_tactics(&board, Move::new(0, 2)).unwrap(), vec![Tactic::Win]);
+
+        let board = Board::from_position(
+            "XO_
+            _O_
+            X__"
+        ).unwrap();
+        assert_eq!(get_tactics(&board, Move::new(1, 0)).unwrap(), vec![Tactic::Win]);
+        // Blocking at (2, 1) also threatens (1, 0) and (2, 2). It's O's win rather than a fork, so nothing is stopped.
+        let tactics = get_tactics(&board, Move::new(2, 1)).unwrap();
+        assert_eq!(tactics, vec![Tactic::Block, Tactic::Fork]);
+        assert_eq!(tactics_to_string(&tactics), "block, fork");
+        assert_eq!(get_tactics(&board, Move::new(1, 2)).unwrap(), vec![Tactic::Threat]);
+
+        assert!(get_tactics(&board, Move::new(0, 0)).is_err());
+        assert!(get_tactics(&Board::from_position("XXXOO____").unwrap(), Move::new(2, 2)).is_err());
+    }
+
+    #[test]
+    fn test_tactics_of_stopping_forks() {
+        // X threatens to fork at (0, 2) or (2, 0).
+        let board = Board::from_position(
+            "X__
+            _O_
+            __X"
+        ).unwrap();
+        // An edge makes X block on a square that doesn't fork, but a corner makes X block on one that does.
+        assert_eq!(get_tactics(&board, Move::new(0, 1)).unwrap(), vec![Tactic::Threat, Tactic::StopsFork]);
+        assert_eq!(get_tactics(&board, Move::new(0, 2)).unwrap(), vec![Tactic::Threat]);
+
+        let board = Board::from_position("X___O____").unwrap();
+        assert_eq!(get_tactics(&board, Move::new(2, 2)).unwrap(), vec![]);
+
+        // O has no fork to stop, so forking at (1, 0) is only a fork.
+        let board = Board::from_position(
+            "XO_
+            _X_
+            __O"
+        ).unwrap();
+        assert!(board.get_fork_moves(Player::O).is_empty());
+        assert_eq!(get_tactics(&board, Move::new(1, 0)).unwrap(), vec![Tactic::Fork]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,211 @@
+use std::io::{self, BufRead, Write};
+
+use crate::board::board::Move;
+use crate::solver::solver::{Evaluation, Solver};
+use crate::tree::tree::{Node, NodeId, Tree};
+
+const HELP: &str = "Commands:
+  <row> <col>   step into the child reached by that move
+  list          list every child with its evaluation and subtree size
+  up            go back to the parent the position was first reached from
+  root          jump back to the root
+  collapse      forget every position below this one, which are generated again when needed
+  show          show the current position
+  help          show this message
+  quit          leave the explorer";
+
+// A position reached along different lines is the same node, so the explorer always shows it, and goes back up from
+// it, along the line it was first reached by.
+pub struct Explorer<'a> {
+    tree: Tree,
+    solver: &'a Solver,
+    current: NodeId,
+}
+
+impl<'a> Explorer<'a> {
+
+    pub fn new(tree: Tree, solver: &'a Solver) -> Self {
+        let current = tree.root_id();
+        Explorer { tree, solver, current }
+    }
+
+    pub fn get_current(&self) -> Node<'_> {
+        self.tree.get_node(self.current)
+    }
+
+    pub fn step(&mut self, m: Move) -> Result<(), String> {
+        let child = self.get_current().get_child(m.get_row(), m.get_column())?;
+        self.current = child.get_id();
+        Ok(())
+    }
+
+    pub fn up(&mut self) -> Result<(), String> {
+        self.current = self.tree.parent(self.current).ok_or("Already at the root".to_string())?;
+        Ok(())
+    }
+
+    pub fn root(&mut self) {
+        self.current = self.tree.root_id();
+    }
+
+    // Returns how many positions were forgotten.
+    pub fn collapse(&mut self) -> Result<usize, String> {
+        let n_positions = self.tree.n_positions();
+        self.tree.collapse(&self.tree.path_to(self.current))?;
+        Ok(n_positions - self.tree.n_positions())
+    }
+
+    pub fn get_children(&self) -> Result<Vec<(Move, Evaluation, usize)>, String> {
+        let current = self.get_current();
+        let mut children = Vec::new();
+        for (m, child) in current.get_legal_moves().into_iter().zip(current.get_children()) {
+            let (evaluation, _) = self.solver.get_evaluation_and_line_for(child.get_board())?;
+            children.push((m, evaluation, child.get_subtree_size()));
+        }
+        Ok(children)
+    }
+
+    pub fn show(&self) -> Result<String, String> {
+        let current = self.get_current();
+        let board = current.get_board();
+        let board_string = match self.tree.move_into(self.current) {
+            Some(m) => board.to_string_with_square_highlighted(m.get_row(), m.get_column()),
+            None => board.to_string(),
+        };
+        let moves = self.tree.path_to(self.current).iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>();
+        let (evaluation, _) = self.solver.get_evaluation_and_line_for(board)?;
+        let to_move = match current.get_active_player() {
+            Some(player) => format!("{} to move", player.to_string()),
+            None => current.get_outcome().to_string(),
+        };
+        Ok(format!(
+            "{}\nMoves: {}\n{}, {}",
+            board_string,
+            match moves.is_empty() {
+                true => "(root)".to_string(),
+                false => moves.join(" "),
+            },
+            to_move,
+            evaluation.to_string(),
+        ))
+    }
+
+    // Runs one line of input, returning what to print, or `None` once the user asks to quit.
+    pub fn execute(&mut self, command: &str) -> Option<Result<String, String>> {
+        let result = match command.trim() {
+            "" => Ok("".to_string()),
+            "quit" | "exit" => return None,
+            "help" => Ok(HELP.to_string()),
+            "show" => self.show(),
+            "root" => {
+                self.root();
+                self.show()
+            },
+            "up" => self.up().and_then(|_| self.show()),
+            "collapse" => self.collapse().map(|forgotten| {
+                format!("Forgot {} positions, {} still generated", forgotten, self.tree.n_positions())
+            }),
+            "list" | "ls" => self.get_children().map(|children| {
+                children.iter()
+                    .map(|(m, evaluation, size)| format!("{}  {:<12}  {} nodes", m.to_string(), evaluation.to_string(), size))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }),
+            other => Move::from_string(other)
+                .and_then(|m| self.step(m))
+                .and_then(|_| self.show()),
+        };
+        Some(result)
+    }
+
+    pub fn run(&mut self, input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{}\n", self.show().map_err(io::Error::other)?)?;
+        write!(out, "> ")?;
+        out.flush()?;
+        for line in input.lines() {
+            match self.execute(&line?) {
+                Some(Ok(text)) => writeln!(out, "{}", text)?,
+                Some(Err(error)) => writeln!(out, "{}", error)?,
+                None => return Ok(()),
+            }
+            write!(out, "\n> ")?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_explorer {
+    use super::*;
+    use crate::board::board::Board;
+
+    #[test]
+    fn test_explorer_moves_around_the_tree() {
+        let board = Board::from_position(
+            "XOX
+            O__
+            XOX",
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let mut explorer = Explorer::new(Tree::lazy(board), &solver);
+
+        assert_eq!(explorer.get_children().unwrap(), vec![
+            (Move::new(1, 1), Evaluation::Loss(0), 1),
+            (Move::new(1, 2), Evaluation::Win(1), 2),
+        ]);
+        assert!(explorer.up().is_err());
+
+        explorer.step(Move::new(1, 2)).unwrap();
+        explorer.step(Move::new(1, 1)).unwrap();
+        assert_eq!(explorer.get_current().get_board(), Board::from_position("XOXOXOXOX").unwrap());
+        assert!(explorer.step(Move::new(0, 0)).is_err());
+
+        explorer.up().unwrap();
+        assert_eq!(explorer.get_current().get_board(), Board::from_position("XOXO_OXOX").unwrap());
+        explorer.root();
+        assert_eq!(explorer.get_current().get_board(), board);
+    }
+
+    #[test]
+    fn test_explorer_follows_the_first_line_into_a_transposition() {
+        let solver = Solver::from_board(Board::empty());
+        let mut explorer = Explorer::new(Tree::lazy(Board::empty()), &solver);
+        for m in [Move::new(0, 0), Move::new(1, 1), Move::new(2, 2)] {
+            explorer.step(m).unwrap();
+        }
+        explorer.root();
+        for m in [Move::new(2, 2), Move::new(1, 1), Move::new(0, 0)] {
+            explorer.step(m).unwrap();
+        }
+        assert!(explorer.show().unwrap().contains("Moves: (0, 0) (1, 1) (2, 2)\n"));
+
+        explorer.up().unwrap();
+        assert_eq!(explorer.get_current().get_board(), Board::from_position("X___O____").unwrap());
+    }
+
+    #[test]
+    fn test_explorer_runs_commands() {
+        let board = Board::from_position(
+            "XOX
+            O__
+            XOX",
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let mut explorer = Explorer::new(Tree::lazy(board), &solver);
+
+        let mut out = Vec::new();
+        explorer.run("list\n1 2\nup\n3 3\ncollapse\nquit\nlist\n".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("XOX\nO__\nXOX\nMoves: (root)\nO to move, O wins in 1\n"));
+        assert!(out.contains("(1, 1)  O has won     1 nodes\n(1, 2)  X wins in 1   2 nodes\n"));
+        assert!(out.contains("Moves: (1, 2)\nX to move, X wins in 1\n"));
+        assert!(out.contains("Invalid move string: 3 3\n"));
+        assert!(out.contains("Forgot 3 positions, 1 still generated\n"));
+        assert_eq!(out.matches("nodes").count(), 2);
+    }
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod explorer;
\ No newline at end of file
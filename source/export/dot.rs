@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use crate::board::board::{Board, Move};
+use crate::solver::solver::{Evaluation, Solver};
+use crate::tree::tree::{Node, Tree};
+
+pub struct DotOptions {
+    pub max_depth: Option<usize>,
+    // Only draw one of each group of sibling moves that lead to symmetric positions.
+    pub collapse_symmetric: bool,
+    // Only expand the solver's line, showing the alternatives at each step as leaves.
+    pub principal_variation_only: bool,
+}
+
+impl DotOptions {
+
+    pub fn new() -> Self {
+        DotOptions { max_depth: None, collapse_symmetric: false, principal_variation_only: false }
+    }
+
+}
+
+pub fn write_dot(tree: &Tree, solver: &Solver, options: &DotOptions, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "digraph tree {{")?;
+    writeln!(out, "    node [shape=box, style=filled, fontname=\"Courier\"];")?;
+    let mut written = HashSet::new();
+    write_node(&tree.get_root(), 0, true, solver, options, &mut written, out)?;
+    writeln!(out, "}}")
+}
+
+fn write_node(
+    node: &Node,
+    depth: usize,
+    on_principal_variation: bool,
+    solver: &Solver,
+    options: &DotOptions,
+    written: &mut HashSet<Board>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let board = node.get_board();
+    if !written.insert(board) {
+        return Ok(());
+    }
+    let (evaluation, line) = solver.get_evaluation_and_line_for(board).map_err(to_io_error)?;
+    writeln!(
+        out,
+        "    {} [label=\"{}\", tooltip=\"{}\", fillcolor=\"{}\"];",
+        node_name(board),
+        board.to_string().replace("\n", "\\n"),
+        evaluation.to_string(),
+        colour(evaluation),
+    )?;
+
+    if options.max_depth.is_some_and(|max_depth| depth >= max_depth) || node.get_active_player().is_none() {
+        return Ok(());
+    }
+    if options.principal_variation_only && !on_principal_variation {
+        return Ok(());
+    }
+
+    let (best_moves, _) = solver.get_next_moves_and_evaluation_for(board).map_err(to_io_error)?;
+    for (moves, child) in group_children(node, options.collapse_symmetric) {
+        let is_best = moves.iter().any(|m| best_moves.contains(m));
+        let label = moves.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" ");
+        let style = match is_best {
+            true => ", style=bold, penwidth=2",
+            false => "",
+        };
+        writeln!(out, "    {} -> {} [label=\"{}\"{}];", node_name(board), node_name(child.get_board()), label, style)?;
+
+        let child_on_principal_variation = on_principal_variation && moves.contains(&line[0]);
+        write_node(&child, depth + 1, child_on_principal_variation, solver, options, written, out)?;
+    }
+    Ok(())
+}
+
+// Pairs each child with the moves leading to it, merging symmetric siblings into the first of them when asked to.
+fn group_children<'a>(node: &Node<'a>, collapse_symmetric: bool) -> Vec<(Vec<Move>, Node<'a>)> {
+    let mut groups: Vec<(Vec<Move>, Node<'a>)> = Vec::new();
+    for (m, child) in node.get_legal_moves().into_iter().zip(node.get_children()) {
+        let existing = match collapse_symmetric {
+            true => groups.iter_mut().find(|(_, other)| other.get_board().canonical().0 == child.get_board().canonical().0),
+            false => None,
+        };
+        match existing {
+            Some((moves, _)) => moves.push(m),
+            None => groups.push((vec![m], child)),
+        }
+    }
+    groups
+}
+
+fn node_name(board: Board) -> String {
+    format!("n{}", board.key())
+}
+
+fn colour(evaluation: Evaluation) -> &'static str {
+    if evaluation.get_score() > 0. {
+        "lightblue"
+    } else if evaluation.get_score() < 0. {
+        "lightpink"
+    } else {
+        "lightgrey"
+    }
+}
+
+fn to_io_error(error: String) -> io::Error {
+    io::Error::other(error)
+}
+
+
+#[cfg(test)]
+mod test_dot {
+    use super::*;
+
+    fn export(position: &str, options: &DotOptions) -> String {
+        let board = Board::from_position(position).unwrap();
+        let mut out = Vec::new();
+        write_dot(&Tree::lazy(board), &Solver::from_board(board), options, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_dot_export_draws_evaluated_boards() {
+        let dot = export(
+            "XOX
+            O__
+            XOX",
+            &DotOptions::new(),
+        );
+        let root = Board::from_position("XOXO__XOX").unwrap();
+        let winning_child = Board::from_position("XOXOO_XOX").unwrap();
+        let losing_child = Board::from_position("XOXO_OXOX").unwrap();
+        assert!(dot.starts_with("digraph tree {\n"));
+        assert!(dot.contains(&format!("    n{} [label=\"XOX\\nO__\\nXOX\", tooltip=\"O wins in 1\", fillcolor=\"lightpink\"];", root.key())));
+        assert!(dot.contains(&format!("    n{} [label=\"XOX\\nO_O\\nXOX\", tooltip=\"X wins in 1\", fillcolor=\"lightblue\"];", losing_child.key())));
+        assert!(dot.contains(&format!("    n{} -> n{} [label=\"(1, 1)\", style=bold, penwidth=2];", root.key(), winning_child.key())));
+        assert!(dot.contains(&format!("    n{} -> n{} [label=\"(1, 2)\"];", root.key(), losing_child.key())));
+        assert_eq!(dot.matches(" -> ").count(), 3);
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_dot_export_limits_depth_and_collapses_symmetries() {
+        let mut options = DotOptions::new();
+        options.max_depth = Some(1);
+        assert_eq!(export("_________", &options).matches(" -> ").count(), 9);
+
+        options.collapse_symmetric = true;
+        let dot = export("_________", &options);
+        assert_eq!(dot.matches(" -> ").count(), 3);
+        assert!(dot.contains("[label=\"(0, 0) (0, 2) (2, 0) (2, 2)\", style=bold, penwidth=2]"));
+    }
+
+    #[test]
+    fn test_dot_export_follows_principal_variation() {
+        let mut options = DotOptions::new();
+        options.principal_variation_only = true;
+        let dot = export(
+            "XO_
+            _X_
+            __O",
+            &options,
+        );
+        // The fastest win takes 3 moves, and every move along it is shown with its alternatives.
+        let (_, line) = Solver::from_board(Board::from_position("XO__X___O").unwrap()).get_evaluation_and_line();
+        assert_eq!(line.len(), 3);
+        assert_eq!(dot.matches(" -> ").count(), 5 + 4 + 3);
+    }
+}
\ No newline at end of file
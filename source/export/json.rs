@@ -0,0 +1,205 @@
+use std::io::{self, Write};
+
+use crate::board::board::{Board, Move};
+use crate::game::game::{Outcome, Player};
+use crate::solver::solver::{Evaluation, Solver};
+use crate::tree::tree::{Tree, Visit, VisitAction, Visitor};
+
+// Bump whenever the shape of the output changes. The schema itself is described in the README.
+pub const SCHEMA_VERSION: u32 = 2;
+
+pub struct JsonOptions {
+    pub max_depth: Option<usize>,
+    // Only include children reached by one of the solver's best moves.
+    pub only_optimal: bool,
+}
+
+impl JsonOptions {
+
+    pub fn new() -> Self {
+        JsonOptions { max_depth: None, only_optimal: false }
+    }
+
+}
+
+// Nodes are written out as they're visited, so the whole document never has to be held in memory.
+pub fn write_json(tree: &Tree, solver: &Solver, options: &JsonOptions, out: &mut impl Write) -> io::Result<()> {
+    write!(out, "{{\"schema_version\":{},\"root\":", SCHEMA_VERSION)?;
+    let mut writer = JsonWriter { solver, options, out: &mut *out, open: Vec::new(), result: Ok(()) };
+    tree.walk(&mut writer);
+    writer.result?;
+    writeln!(out, "}}")
+}
+
+// Opens each node as it's entered and closes it as it's left. A child that's left out is still entered, but
+// pruned without anything being written.
+struct JsonWriter<'a, W: Write> {
+    solver: &'a Solver,
+    options: &'a JsonOptions,
+    out: &'a mut W,
+    // For every node entered and not left yet, the best moves out of it and whether one of its children has been
+    // written, or `None` if the node itself was left out.
+    open: Vec<Option<(Vec<Move>, bool)>>,
+    // Nothing more is written after the first error.
+    result: io::Result<()>,
+}
+
+impl<'a, W: Write> JsonWriter<'a, W> {
+
+    // Writes the node up to the opening of its children, and gives the best moves out of it if they're to be written.
+    fn write_node(&mut self, visit: &Visit, move_in: Option<(Move, bool)>) -> io::Result<Option<Vec<Move>>> {
+        if let Some(Some((_, any_written))) = self.open.last_mut() {
+            if *any_written {
+                write!(self.out, ",")?;
+            }
+            *any_written = true;
+        }
+        let node = &visit.node;
+        let board = node.get_board();
+        let (evaluation, _) = self.solver.get_evaluation_and_line_for(board).map_err(io::Error::other)?;
+        write!(self.out, "{{\"board\":{}", board_json(board))?;
+        match move_in {
+            Some((m, optimal)) => write!(self.out, ",\"move\":[{},{}],\"optimal\":{}", m.get_row(), m.get_column(), optimal)?,
+            None => write!(self.out, ",\"move\":null,\"optimal\":null")?,
+        }
+        write!(self.out, ",\"to_move\":{}", player_json(node.get_active_player()))?;
+        write!(self.out, ",\"outcome\":\"{}\"", outcome_json(node.get_outcome()))?;
+        write!(self.out, ",\"evaluation\":{}", evaluation_json(evaluation))?;
+
+        let can_expand = self.options.max_depth.is_none_or(|max_depth| visit.depth < max_depth);
+        write!(self.out, ",\"expanded\":{}", can_expand || node.get_active_player().is_none())?;
+        write!(self.out, ",\"children\":[")?;
+        match can_expand && node.get_active_player().is_some() {
+            true => Ok(Some(self.solver.get_next_moves_and_evaluation_for(board).map_err(io::Error::other)?.0)),
+            false => Ok(None),
+        }
+    }
+
+}
+
+impl<'a, W: Write> Visitor for JsonWriter<'a, W> {
+
+    fn enter(&mut self, visit: &Visit) -> VisitAction {
+        let move_in = match (visit.path.last(), self.open.last()) {
+            (Some(m), Some(Some((best_moves, _)))) => Some((*m, best_moves.contains(m))),
+            _ => None,
+        };
+        let left_out = self.options.only_optimal && move_in.is_some_and(|(_, optimal)| !optimal);
+        if self.result.is_err() || left_out {
+            self.open.push(None);
+            return VisitAction::Prune;
+        }
+        match self.write_node(visit, move_in) {
+            Ok(Some(best_moves)) => {
+                self.open.push(Some((best_moves, false)));
+                VisitAction::Continue
+            },
+            Ok(None) => {
+                self.open.push(Some((Vec::new(), false)));
+                VisitAction::Prune
+            },
+            Err(error) => {
+                self.result = Err(error);
+                self.open.push(None);
+                VisitAction::Prune
+            },
+        }
+    }
+
+    fn leave(&mut self, _visit: &Visit) {
+        if let Some(Some(_)) = self.open.pop() {
+            if self.result.is_ok() {
+                self.result = write!(self.out, "]}}");
+            }
+        }
+    }
+
+}
+
+fn board_json(board: Board) -> String {
+    let rows = board.to_string().lines().map(|row| format!("\"{}\"", row)).collect::<Vec<String>>();
+    format!("[{}]", rows.join(","))
+}
+
+fn player_json(player: Option<Player>) -> String {
+    match player {
+        Some(player) => format!("\"{}\"", player.to_string()),
+        None => "null".to_string(),
+    }
+}
+
+fn outcome_json(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Victory(Player::X) => "x_wins",
+        Outcome::Victory(Player::O) => "o_wins",
+        Outcome::Draw => "draw",
+        Outcome::InProgress => "in_progress",
+        Outcome::Ambiguous => "ambiguous",
+    }
+}
+
+fn evaluation_json(evaluation: Evaluation) -> String {
+    let plies = match evaluation.get_plies() {
+        Some(plies) => plies.to_string(),
+        None => "null".to_string(),
+    };
+    format!("{{\"score\":{:?},\"plies\":{},\"text\":\"{}\"}}", evaluation.get_score(), plies, evaluation.to_string())
+}
+
+
+#[cfg(test)]
+mod test_json {
+    use super::*;
+
+    fn export(position: &str, options: &JsonOptions) -> String {
+        let board = Board::from_position(position).unwrap();
+        let mut out = Vec::new();
+        write_json(&Tree::lazy(board), &Solver::from_board(board), options, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_export_writes_nodes() {
+        let json = export(
+            "XOX
+            O_O
+            XOX",
+            &JsonOptions::new(),
+        );
+        assert_eq!(
+            json,
+            concat!(
+                "{\"schema_version\":2,\"root\":",
+                "{\"board\":[\"XOX\",\"O_O\",\"XOX\"],\"move\":null,\"optimal\":null,\"to_move\":\"X\",\"outcome\":\"in_progress\",",
+                "\"evaluation\":{\"score\":1.0,\"plies\":1,\"text\":\"X wins in 1\"},\"expanded\":true,\"children\":[",
+                "{\"board\":[\"XOX\",\"OXO\",\"XOX\"],\"move\":[1,1],\"optimal\":true,\"to_move\":null,\"outcome\":\"x_wins\",",
+                "\"evaluation\":{\"score\":1.0,\"plies\":0,\"text\":\"X has won\"},\"expanded\":true,\"children\":[]}",
+                "]}}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_export_limits_depth_and_filters_optimal_moves() {
+        let mut options = JsonOptions::new();
+        options.max_depth = Some(1);
+        let json = export(
+            "XO_
+            _X_
+            __O",
+            &options,
+        );
+        assert_eq!(json.matches("\"move\":[").count(), 5);
+        assert_eq!(json.matches("\"expanded\":false").count(), 5);
+
+        options.only_optimal = true;
+        let json = export(
+            "XO_
+            _X_
+            __O",
+            &options,
+        );
+        assert_eq!(json.matches("\"move\":[").count(), 2);
+        assert_eq!(json.matches("\"optimal\":false").count(), 0);
+    }
+}
\ No newline at end of file
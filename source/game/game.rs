@@ -0,0 +1,215 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Everything the tree and the solver need to know about a two-player game. Positions are small values, and making
+// a move gives a new position rather than changing the old one. Every way of reaching a position has to take the same
+// number of moves, since the tree keeps a single depth for each position it shares between paths.
+pub trait Game: Copy + Eq + Hash + Debug {
+    // Children are listed in the order of their moves, which is row-major for `Board`.
+    type Move: Copy + Eq + Ord + Hash + Debug;
+    // The symmetries that positions can be shared across. A game without any has only the identity.
+    type Symmetry: GameSymmetry<Self>;
+
+    fn get_legal_moves(&self) -> Vec<Self::Move>;
+
+    // Makes a move for the side to move.
+    fn apply(&self, m: Self::Move) -> Result<Self, String>;
+
+    fn get_outcome(&self) -> Outcome;
+
+    // `None` once the game is over.
+    fn get_active_player(&self) -> Option<Player>;
+
+    // One position standing for every position symmetric to this one, and the symmetry mapping this one onto it.
+    fn canonical(&self) -> (Self, Self::Symmetry);
+
+    // Puts the moves the solver should try first at the front. The solver is correct in any order, just slower.
+    fn order_moves(&self, _moves: &mut Vec<Self::Move>) {}
+
+    // What a search that stops before the end of the game uses to guess at the positions it stopped at.
+    fn default_evaluator() -> Box<dyn Evaluator<Self>> {
+        Box::new(Flat)
+    }
+
+    // How a position is shown in error messages.
+    fn to_string(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+pub trait GameSymmetry<G: Game>: Copy + Eq + Hash + Debug {
+    fn identity() -> Self;
+
+    fn all() -> Vec<Self>;
+
+    // The symmetry that applies `other` first and then `self`.
+    fn compose(&self, other: &Self) -> Self;
+
+    fn inverse(&self) -> Self;
+
+    fn transform(&self, position: &G) -> G;
+
+    fn transform_move(&self, m: G::Move) -> G::Move;
+}
+
+// Guesses how good an unfinished position is, for searches that stop before the end of the game. Scores are from
+// X's point of view: positive when X is better off, negative when O is, and only compared with each other. A
+// parallel search shares one evaluator between all its threads.
+pub trait Evaluator<G: Game>: Send + Sync {
+    fn evaluate(&self, position: &G) -> i32;
+}
+
+// Knows nothing, so every unfinished position gets the same score.
+pub struct Flat;
+
+impl<G: Game> Evaluator<G> for Flat {
+    fn evaluate(&self, _position: &G) -> i32 {
+        0
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Victory(Player),
+    Draw,
+    InProgress,
+    Ambiguous,
+}
+
+impl Outcome {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Outcome::Victory(player) => format!("{} wins", player.to_string()),
+            Outcome::Draw => "Draw".to_string(),
+            Outcome::InProgress => "Game in progress".to_string(),
+            Outcome::Ambiguous => "Ambiguous".to_string(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Player {
+    X,
+    O,
+}
+
+impl Player {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Player::X => "X".to_string(),
+            Player::O => "O".to_string(),
+        }
+    }
+
+    pub fn get_opponent(&self) -> Player {
+        match self {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_game {
+    use super::*;
+    use crate::solver::solver::{Evaluation, Solver};
+    use crate::tree::tree::Tree;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct NoSymmetry;
+
+    impl<G: Game> GameSymmetry<G> for NoSymmetry {
+        fn identity() -> Self {
+            NoSymmetry
+        }
+
+        fn all() -> Vec<Self> {
+            vec![NoSymmetry]
+        }
+
+        fn compose(&self, _other: &Self) -> Self {
+            NoSymmetry
+        }
+
+        fn inverse(&self) -> Self {
+            NoSymmetry
+        }
+
+        fn transform(&self, position: &G) -> G {
+            *position
+        }
+
+        fn transform_move(&self, m: G::Move) -> G::Move {
+            m
+        }
+    }
+
+    // Players take one or two stones from a pile in turn, and whoever takes the last stone wins.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Pile {
+        stones: usize,
+        to_move: Player,
+    }
+
+    impl Game for Pile {
+        type Move = usize;
+        type Symmetry = NoSymmetry;
+
+        fn get_legal_moves(&self) -> Vec<usize> {
+            match self.get_active_player() {
+                Some(_) => (1..=2).filter(|taken| *taken <= self.stones).collect(),
+                None => Vec::new(),
+            }
+        }
+
+        fn apply(&self, taken: usize) -> Result<Self, String> {
+            if !self.get_legal_moves().contains(&taken) {
+                return Err(format!("Can't take {} stones", taken));
+            }
+            let to_move = match self.to_move {
+                Player::X => Player::O,
+                Player::O => Player::X,
+            };
+            Ok(Pile { stones: self.stones - taken, to_move })
+        }
+
+        fn get_outcome(&self) -> Outcome {
+            match self.stones {
+                0 => Outcome::Victory(self.to_move.get_opponent()),
+                _ => Outcome::InProgress,
+            }
+        }
+
+        fn get_active_player(&self) -> Option<Player> {
+            match self.stones {
+                0 => None,
+                _ => Some(self.to_move),
+            }
+        }
+
+        fn canonical(&self) -> (Self, NoSymmetry) {
+            (*self, NoSymmetry)
+        }
+    }
+
+    #[test]
+    fn test_solver_plays_other_games() {
+        for stones in 1..10 {
+            let solver = Solver::from_board(Pile { stones, to_move: Player::X });
+            // Leaving a multiple of three is the only way to win.
+            match stones % 3 {
+                0 => assert_eq!(solver.get_evaluation(), Evaluation::Loss(2 * stones / 3)),
+                _ => {
+                    assert_eq!(solver.get_next_moves(), Ok(vec![stones % 3]));
+                    assert!(solver.get_evaluation() > Evaluation::Draw);
+                },
+            }
+        }
+
+        let tree = Tree::from_board(Pile { stones: 4, to_move: Player::X });
+        assert_eq!(tree.n_positions(), 8);
+        assert_eq!(tree.get_root().get_child_by_move(2).unwrap().get_board(), Pile { stones: 2, to_move: Player::O });
+    }
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod game;
\ No newline at end of file
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use crate::board::board::{Board, Move, Symmetry};
+use crate::solver::solver::Solver;
+
+pub struct LineOptions {
+    // Stop after this many lines.
+    pub max_lines: Option<usize>,
+    // Only give one line from each group of lines that are mirror images or rotations of each other.
+    pub up_to_symmetry: bool,
+}
+
+impl LineOptions {
+
+    pub fn new() -> Self {
+        LineOptions { max_lines: None, up_to_symmetry: false }
+    }
+
+}
+
+// Every line of best moves from `board` to the end of the game, in row-major order of their moves.
+pub fn get_optimal_lines(solver: &Solver, board: Board, options: &LineOptions) -> Result<Vec<Vec<Move>>, String> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    collect_lines(solver, board, &initial_symmetries(board, options.up_to_symmetry), options.max_lines, &mut line, &mut lines)?;
+    Ok(lines)
+}
+
+pub fn count_optimal_lines(solver: &Solver, board: Board, up_to_symmetry: bool) -> Result<u64, String> {
+    count_lines(solver, board, &initial_symmetries(board, up_to_symmetry), &mut HashMap::new())
+}
+
+// Two lines only count as the same when a symmetry maps one onto the other, and that symmetry has to leave every
+// position along the way unchanged. So only the symmetries of the position reached so far are kept.
+fn initial_symmetries(board: Board, up_to_symmetry: bool) -> Vec<Symmetry> {
+    match up_to_symmetry {
+        true => Symmetry::all().into_iter().filter(|symmetry| board.transformed(symmetry) == board).collect(),
+        false => vec![Symmetry::identity()],
+    }
+}
+
+// The best moves, keeping only the first of any that the remaining symmetries map onto each other.
+fn get_distinct_best_moves(solver: &Solver, board: Board, symmetries: &[Symmetry]) -> Result<Option<Vec<Move>>, String> {
+    if board.get_active_player().is_none() {
+        return Ok(None);
+    }
+    let (best_moves, _) = solver.get_next_moves_and_evaluation_for(board)?;
+    let distinct = best_moves.iter()
+        .filter(|m| symmetries.iter().all(|symmetry| {
+            let image = m.transformed(symmetry);
+            (image.get_row(), image.get_column()) >= (m.get_row(), m.get_column())
+        }))
+        .copied()
+        .collect();
+    Ok(Some(distinct))
+}
+
+fn symmetries_fixing(symmetries: &[Symmetry], m: Move) -> Vec<Symmetry> {
+    symmetries.iter().filter(|symmetry| m.transformed(symmetry) == m).copied().collect()
+}
+
+fn collect_lines(
+    solver: &Solver,
+    board: Board,
+    symmetries: &[Symmetry],
+    max_lines: Option<usize>,
+    line: &mut Vec<Move>,
+    lines: &mut Vec<Vec<Move>>,
+) -> Result<(), String> {
+    let best_moves = match get_distinct_best_moves(solver, board, symmetries)? {
+        Some(best_moves) => best_moves,
+        None => {
+            lines.push(line.clone());
+            return Ok(());
+        },
+    };
+    for m in best_moves {
+        if max_lines.is_some_and(|max_lines| lines.len() >= max_lines) {
+            break;
+        }
+        let child = board.with_move_made(board.get_active_player().unwrap(), m)?;
+        line.push(m);
+        collect_lines(solver, child, &symmetries_fixing(symmetries, m), max_lines, line, lines)?;
+        line.pop();
+    }
+    Ok(())
+}
+
+fn count_lines(
+    solver: &Solver,
+    board: Board,
+    symmetries: &[Symmetry],
+    counted: &mut HashMap<(Board, Vec<Symmetry>), u64>,
+) -> Result<u64, String> {
+    if let Some(count) = counted.get(&(board, symmetries.to_vec())) {
+        return Ok(*count);
+    }
+    let count = match get_distinct_best_moves(solver, board, symmetries)? {
+        Some(best_moves) => {
+            let mut count = 0;
+            for m in best_moves {
+                let child = board.with_move_made(board.get_active_player().unwrap(), m)?;
+                count += count_lines(solver, child, &symmetries_fixing(symmetries, m), counted)?;
+            }
+            count
+        },
+        None => 1,
+    };
+    counted.insert((board, symmetries.to_vec()), count);
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test_lines {
+    use super::*;
+    use crate::game::game::Player;
+
+    #[test]
+    fn test_lines_are_all_optimal() {
+        let board = Board::from_position(
+            "X__
+            _O_
+            ___"
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let lines = get_optimal_lines(&solver, board, &LineOptions::new()).unwrap();
+        assert_eq!(lines.len() as u64, count_optimal_lines(&solver, board, false).unwrap());
+
+        let (evaluation, line) = solver.get_evaluation_and_line();
+        assert!(lines.contains(&line));
+        for line in lines.iter() {
+            let mut current = board;
+            for m in line.iter() {
+                assert!(solver.get_next_moves_and_evaluation_for(current).unwrap().0.contains(m));
+                current = current.with_move_made(current.get_active_player().unwrap(), *m).unwrap();
+            }
+            assert_eq!(current.get_active_player(), None);
+            assert_eq!(line.len(), lines[0].len());
+        }
+        assert_eq!(evaluation, solver.get_evaluation_and_line_for(board).unwrap().0);
+
+        let mut options = LineOptions::new();
+        options.max_lines = Some(3);
+        assert_eq!(get_optimal_lines(&solver, board, &options).unwrap(), lines[..3].to_vec());
+    }
+
+    #[test]
+    fn test_lines_up_to_symmetry() {
+        let solver = Solver::from_board(Board::empty());
+        let all = count_optimal_lines(&solver, Board::empty(), false).unwrap();
+        let distinct = count_optimal_lines(&solver, Board::empty(), true).unwrap();
+        assert_eq!(all, 3584);
+        // A finished game can't be its own mirror image, so every class has all 8 of its symmetric lines.
+        assert_eq!(distinct, 448);
+
+        let mut options = LineOptions::new();
+        options.up_to_symmetry = true;
+        let lines = get_optimal_lines(&solver, Board::empty(), &options).unwrap();
+        assert_eq!(lines.len() as u64, distinct);
+        assert_eq!(lines.iter().filter(|line| line[0] == Move::new(0, 0)).count() as u64, {
+            let corner = Board::empty().with_move_made(Player::X, Move::new(0, 0)).unwrap();
+            count_optimal_lines(&solver, corner, true).unwrap()
+        });
+        assert!(lines.iter().all(|line| [Move::new(0, 0), Move::new(0, 1), Move::new(1, 1)].contains(&line[0])));
+    }
+
+    #[test]
+    fn test_lines_of_a_finished_game() {
+        let board = Board::from_position("XXXOO____").unwrap();
+        let solver = Solver::from_board(board);
+        assert_eq!(get_optimal_lines(&solver, board, &LineOptions::new()).unwrap(), vec![Vec::<Move>::new()]);
+        assert_eq!(count_optimal_lines(&solver, board, true).unwrap(), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod lines;
\ No newline at end of file
@@ -1,10 +1,35 @@
 mod board;
+mod game;
 mod tree;
 mod solver;
+mod export;
+mod explorer;
+mod table;
+mod search;
+mod lines;
+mod mcts;
 
-use clap::{App, SubCommand, Arg};
-use crate::board::board::{Board, Move, Outcome};
+use std::fs::File;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use clap::{App, SubCommand, Arg, ArgGroup, ArgMatches};
+use crate::board::board::{Board, Move};
+use crate::board::heuristic::LineCounting;
+use crate::board::tactics::{get_tactics, tactics_to_string};
+use crate::game::game::{Evaluator, Flat};
 use crate::solver::solver::Solver;
+use crate::solver::deepening::SearchLimits;
+use crate::solver::proof::ProofNumberSearch;
+use crate::tree::tree::Tree;
+use crate::export::dot::{DotOptions, write_dot};
+use crate::export::json::{JsonOptions, write_json};
+use crate::explorer::explorer::Explorer;
+use crate::table::table::{Table, build_table, write_table};
+use crate::table::tablebase::Tablebase;
+use crate::search::search::Query;
+use crate::lines::lines::{LineOptions, count_optimal_lines, get_optimal_lines};
+use crate::mcts::mcts::{Mcts, MctsOptions, RolloutPolicy};
 
 
 
@@ -22,6 +47,254 @@ fn main() {
                         .help("Show an example optimal line from the best move")
                         .short('l')
                         .long("line")
+                ).arg(
+                    Arg::with_name("Table")
+                        .help("Answer from a table made by build-table instead of searching")
+                        .short('t')
+                        .long("table")
+                        .takes_value(true)
+                        .conflicts_with_all(&["Time", "Nodes", "Depth"])
+                ).arg(
+                    Arg::with_name("Read Table")
+                        .help("Read the --table file into memory instead of mapping it, so later changes to it can't affect the answers")
+                        .long("read-table")
+                ).arg(
+                    Arg::with_name("Share Symmetries")
+                        .help("Solve symmetric positions only once")
+                        .short('s')
+                        .long("share-symmetries")
+                ).arg(
+                    Arg::with_name("Retrograde")
+                        .help("Answer from a tablebase generated backwards from finished games")
+                        .short('r')
+                        .long("retrograde")
+                        .conflicts_with_all(&["Time", "Nodes", "Depth"])
+                ).arg(
+                    Arg::with_name("Threads")
+                        .help("Find the evaluation and best moves on this many threads; --line still searches on one")
+                        .long("threads")
+                        .takes_value(true)
+                        .conflicts_with_all(&["Time", "Nodes", "Depth"])
+                ).arg(
+                    Arg::with_name("Prefer Traps")
+                        .help("Order the best moves by how many of the opponent's replies go wrong")
+                        .long("prefer-traps")
+                ).arg(
+                    Arg::with_name("Prove")
+                        .help("Only prove or disprove a win for the player to move, expanding at most --nodes positions")
+                        .long("prove")
+                ).arg(
+                    Arg::with_name("Time")
+                        .help("Search one ply deeper at a time, stopping after this many milliseconds")
+                        .long("time-ms")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Nodes")
+                        .help("Search one ply deeper at a time, stopping after this many positions")
+                        .long("nodes")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Depth")
+                        .help("Search one ply deeper at a time, stopping at this many plies")
+                        .short('d')
+                        .long("depth")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Evaluator")
+                        .help("How to guess at the positions --time-ms, --nodes or --depth stop at: lines or flat")
+                        .long("evaluator")
+                        .takes_value(true)
+                        .requires("Limits")
+                        .conflicts_with("Prove")
+                ).group(
+                    ArgGroup::with_name("Limits")
+                        .args(&["Time", "Nodes", "Depth"])
+                        .multiple(true)
+                ).arg(
+                    Arg::with_name("Stats")
+                        .help("Also print how many positions the solver solved and how many nodes it searched")
+                        .long("stats")
+                )
+            ).subcommand(
+			SubCommand::with_name("export-dot")
+				.about("Export the game tree from a position as a Graphviz graph")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				).arg(
+                    Arg::with_name("Depth")
+                        .help("Only export this many moves below the position")
+                        .short('d')
+                        .long("depth")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Collapse Symmetric")
+                        .help("Draw sibling moves that lead to symmetric positions once")
+                        .short('s')
+                        .long("collapse-symmetric")
+                ).arg(
+                    Arg::with_name("Principal Variation")
+                        .help("Only expand the optimal line, showing the alternatives at each step")
+                        .short('p')
+                        .long("principal-variation")
+                ).arg(
+                    Arg::with_name("Output")
+                        .help("File to write the graph to, instead of stdout")
+                        .short('o')
+                        .long("output")
+                        .takes_value(true)
+                )
+            ).subcommand(
+			SubCommand::with_name("export-json")
+				.about("Export the solved game tree from a position as JSON")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				).arg(
+                    Arg::with_name("Depth")
+                        .help("Only export this many moves below the position")
+                        .short('d')
+                        .long("depth")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Only Optimal")
+                        .help("Only export children reached by an optimal move")
+                        .long("only-optimal")
+                ).arg(
+                    Arg::with_name("Output")
+                        .help("File to write the JSON to, instead of stdout")
+                        .short('o')
+                        .long("output")
+                        .takes_value(true)
+                )
+            ).subcommand(
+			SubCommand::with_name("explore")
+				.about("Interactively walk the game tree from a position")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				).arg(
+                    Arg::with_name("Depth")
+                        .help("Never generate positions more than this many moves below the position")
+                        .long("max-depth")
+                        .takes_value(true)
+                )
+            ).subcommand(
+			SubCommand::with_name("build-table")
+				.about("Solve every position and write the results to a table file")
+				.arg(
+					Arg::with_name("Output")
+						.help("File to write the table to")
+                        .required(true)
+				).arg(
+                    Arg::with_name("Retrograde")
+                        .help("Work backwards from finished games instead of searching forwards")
+                        .short('r')
+                        .long("retrograde")
+                ).arg(
+                    Arg::with_name("Check")
+                        .help("With --retrograde, check every position against the forward solver first")
+                        .long("check")
+                )
+            ).subcommand(
+			SubCommand::with_name("search")
+				.about("Find reachable positions matching a query, e.g. \"fork=X to-move=O\"")
+				.arg(
+					Arg::with_name("Query")
+						.help("Space separated terms: pattern=X?O______ to-move=X|O|none outcome=x-wins|o-wins|draw|in-progress eval=x-wins|o-wins|draw fork=X|O best=corner|edge|centre best-count=N non-losing=corner|edge|centre non-losing-count=N")
+                        .required(true)
+				).arg(
+                    Arg::with_name("From")
+                        .help("Only search positions reachable from this one")
+                        .short('f')
+                        .long("from")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Canonical")
+                        .help("Only consider one position from each symmetry class")
+                        .short('c')
+                        .long("canonical")
+                ).arg(
+                    Arg::with_name("Count")
+                        .help("Only print how many positions match")
+                        .long("count")
+                )
+            ).subcommand(
+			SubCommand::with_name("analyze")
+				.about("Evaluate every legal move in a position, best first")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				)
+            ).subcommand(
+			SubCommand::with_name("why")
+				.about("Show how the opponent punishes a move that isn't one of the best")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+						.required(true)
+				).arg(
+					Arg::with_name("Move")
+						.help("The move to explain, as row and column, e.g. 01")
+						.required(true)
+				)
+            ).subcommand(
+			SubCommand::with_name("play")
+				.about("Play the game out from a position, with the solver making every move")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				)
+            ).subcommand(
+			SubCommand::with_name("lines")
+				.about("List every line of best moves from a position to the end of the game")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				).arg(
+                    Arg::with_name("Max Lines")
+                        .help("Stop after this many lines")
+                        .short('m')
+                        .long("max")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Symmetric")
+                        .help("Only list one line from each group of symmetric lines")
+                        .short('s')
+                        .long("symmetric")
+                ).arg(
+                    Arg::with_name("Count")
+                        .help("Only print how many lines there are")
+                        .long("count")
+                )
+            ).subcommand(
+			SubCommand::with_name("mcts")
+				.about("Estimate the best move with Monte Carlo tree search and compare it with the solver's")
+				.arg(
+					Arg::with_name("Position")
+						.help("Tic Tac Toe Position")
+				).arg(
+                    Arg::with_name("Iterations")
+                        .help("How many games to play out")
+                        .short('i')
+                        .long("iterations")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Exploration")
+                        .help("How strongly to favour moves that have been tried less")
+                        .short('c')
+                        .long("exploration")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Seed")
+                        .help("Seed for the random number generator")
+                        .long("seed")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Rollout")
+                        .help("How to play games out: random or wins-first")
+                        .long("rollout")
+                        .takes_value(true)
                 )
             ).get_matches();
 
@@ -30,7 +303,70 @@ fn main() {
             Some(position) => {
                 match Board::from_position(position) {
                     Ok(board) => {
-                        let mut solver = Solver::from_board(board);
+                        // Proofs run on the board alone, so they don't wait for a solver to be set up.
+                        if matches.is_present("Prove") {
+                            match get_limits(matches).and_then(|limits| {
+                                Ok(ProofNumberSearch::new(board)?.search(limits.nodes.unwrap_or(usize::MAX)))
+                            }) {
+                                Ok(result) => println!("\n\n{}", result),
+                                Err(error) => println!("{}", error),
+                            }
+                            return;
+                        }
+                        if ["Time", "Nodes", "Depth"].iter().any(|name| matches.is_present(name)) {
+                            match get_limits(matches).and_then(|limits| {
+                                Solver::from_board(board).with_evaluator(get_evaluator(matches)?).search_iteratively(&limits)
+                            }) {
+                                Ok(result) => println!("\n\nEvaluation: {}\nBest move: {}", result, result.best_move.to_string()),
+                                Err(error) => println!("{}", error),
+                            }
+                            return;
+                        }
+                        let solver = match matches.value_of("Table") {
+                            Some(path) => match open_table(matches, path).and_then(|table| Solver::from_table(board, table)) {
+                                Ok(solver) => solver,
+                                Err(error) => {
+                                    println!("{}", error);
+                                    return;
+                                }
+                            },
+                            None if matches.is_present("Retrograde") => match Solver::from_tablebase(board, Tablebase::generate()) {
+                                Ok(solver) => solver,
+                                Err(error) => {
+                                    println!("{}", error);
+                                    return;
+                                }
+                            },
+                            None => {
+                                let solver = match matches.is_present("Share Symmetries") {
+                                    true => Solver::from_board(board).sharing_symmetries(),
+                                    false => Solver::from_board(board),
+                                };
+                                match get_threads(matches).and_then(|threads| solver.with_threads(threads)) {
+                                    Ok(solver) => solver,
+                                    Err(error) => {
+                                        println!("{}", error);
+                                        return;
+                                    }
+                                }
+                            },
+                        };
+                        if matches.is_present("Prefer Traps") {
+                            match solver.get_trap_scores() {
+                                Ok((scores, evaluation)) => {
+                                    let scores_string = scores.iter()
+                                        .map(|score| format!("{}  {} of {} replies go wrong", score.m.to_string(), score.losing_replies, score.replies))
+                                        .collect::<Vec<String>>()
+                                        .join("\n");
+                                    println!("\n\nEvaluation: {}\nBest moves, most testing first:\n{}", evaluation.to_string(), scores_string);
+                                    if let Some(score) = scores.first() {
+                                        println!("Most testing move: {}", score.m.to_string());
+                                    }
+                                },
+                                Err(error) => println!("{}", error),
+                            }
+                            return;
+                        }
                         match matches.is_present("Show Line") {
                             true => {
                                 match solver.get_evaluation_and_line() {
@@ -62,7 +398,10 @@ fn main() {
                                 match solver.get_next_moves_and_evaluation() {
                                     Ok((next_moves, evaluation)) => {
                                         let next_moves_string = next_moves.iter()
-                                            .map(|x| x.to_string())
+                                            .map(|m| match get_tactics(&board, *m) {
+                                                Ok(tactics) if !tactics.is_empty() => format!("{:<8}{}", m.to_string(), tactics_to_string(&tactics)),
+                                                _ => m.to_string(),
+                                            })
                                             .collect::<Vec<String>>()
                                             .join("\n");
 
@@ -74,6 +413,10 @@ fn main() {
                                 }
                             }
                         }
+                        if matches.is_present("Stats") {
+                            println!("\nSolved positions: {}", solver.n_solved_positions());
+                            println!("Nodes searched: {}", solver.n_nodes_searched());
+                        }
                     },
                     Err(error) => {
                         println!("{}", error);
@@ -84,11 +427,261 @@ fn main() {
                 println!("Needs a Position!");
             }
         }
+    } else if let Some(matches) = matches.subcommand_matches("export-dot") {
+        if let Err(error) = export_dot(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("export-json") {
+        if let Err(error) = export_json(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("explore") {
+        if let Err(error) = explore(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("search") {
+        if let Err(error) = search(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("analyze") {
+        if let Err(error) = analyze(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("why") {
+        if let Err(error) = why(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("play") {
+        if let Err(error) = play(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("lines") {
+        if let Err(error) = lines(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("mcts") {
+        if let Err(error) = mcts(matches) {
+            println!("{}", error);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("build-table") {
+        if let Err(error) = build_table_file(matches) {
+            println!("{}", error);
+        }
     } else {
         println!("Invalid command!");
     }
 }
 
+fn export_dot(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let mut options = DotOptions::new();
+    options.max_depth = get_depth(matches)?;
+    options.collapse_symmetric = matches.is_present("Collapse Symmetric");
+    options.principal_variation_only = matches.is_present("Principal Variation");
+
+    let tree = Tree::lazy(board);
+    let solver = Solver::from_board(board);
+    write_dot(&tree, &solver, &options, &mut open_output(matches)?).map_err(|error| error.to_string())
+}
+
+fn export_json(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let mut options = JsonOptions::new();
+    options.max_depth = get_depth(matches)?;
+    options.only_optimal = matches.is_present("Only Optimal");
+
+    let tree = Tree::lazy(board);
+    let solver = Solver::from_board(board);
+    write_json(&tree, &solver, &options, &mut open_output(matches)?).map_err(|error| error.to_string())
+}
+
+fn explore(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let tree = match get_depth(matches)? {
+        Some(depth) => Tree::lazy(board).with_max_depth(depth),
+        None => Tree::lazy(board),
+    };
+    let solver = Solver::from_board(board);
+    let mut explorer = Explorer::new(tree, &solver);
+    explorer.run(io::stdin().lock(), &mut io::stdout()).map_err(|error| error.to_string())
+}
+
+fn build_table_file(matches: &ArgMatches) -> Result<(), String> {
+    let entries = match matches.is_present("Retrograde") {
+        true => {
+            let tablebase = Tablebase::generate();
+            if matches.is_present("Check") {
+                tablebase.check(&Solver::from_board(Board::empty()))?;
+                println!("Checked {} positions against the forward solver", tablebase.len());
+            }
+            tablebase.get_entries()
+        },
+        false => build_table(),
+    };
+    write_table(&entries, &mut open_output(matches)?).map_err(|error| error.to_string())?;
+    println!("Wrote {} positions", entries.len());
+    Ok(())
+}
+
+fn search(matches: &ArgMatches) -> Result<(), String> {
+    let query = Query::from_string(matches.value_of("Query").ok_or("Needs a Query!")?)?;
+    let root = match matches.value_of("From") {
+        Some(position) => Board::from_position(position)?,
+        None => Board::empty(),
+    };
+    let found = query.search(root, matches.is_present("Canonical"));
+    if !matches.is_present("Count") {
+        for board in found.iter() {
+            println!("{}\n", board.to_string());
+        }
+    }
+    println!("{} positions match", found.len());
+    Ok(())
+}
+
+fn analyze(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let analysis = Solver::from_board(board).analyze()?;
+    let best_moves = analysis.get_best_moves().iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" ");
+    println!("{}\n\n{}\n\nBest moves: {}", board.to_string(), analysis, best_moves);
+    Ok(())
+}
+
+fn why(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let m = Move::from_string(matches.value_of("Move").ok_or("Needs a Move!")?)?;
+    let refutation = Solver::from_board(board).refute(m)?;
+    println!("{}\n\n{}", m.to_string_on_board(&board)?, refutation);
+    Ok(())
+}
+
+// The solver moves on with the game, so it only keeps what it solved about positions that can still come up.
+fn play(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let mut solver = Solver::from_board(board);
+    println!("{}", board.to_string());
+    while let Ok((best_moves, evaluation)) = solver.get_next_moves_and_evaluation() {
+        let (before, m) = (solver.get_board(), best_moves[0]);
+        solver.play(m)?;
+        println!(
+            "\n{} plays {} ({})\n{}",
+            before.get_active_player().expect("A position with best moves has a player to move.").to_string(),
+            m.to_string(),
+            evaluation.to_string(),
+            solver.get_board().to_string_with_square_highlighted(m.get_row(), m.get_column()),
+        );
+    }
+    println!("\n{}", solver.get_board().get_outcome().to_string());
+    Ok(())
+}
+
+fn lines(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let solver = Solver::from_board(board);
+    let up_to_symmetry = matches.is_present("Symmetric");
+    if matches.is_present("Count") {
+        println!("{} optimal lines", count_optimal_lines(&solver, board, up_to_symmetry)?);
+        return Ok(());
+    }
+    let mut options = LineOptions::new();
+    options.up_to_symmetry = up_to_symmetry;
+    options.max_lines = match matches.value_of("Max Lines") {
+        Some(max_lines) => Some(max_lines.parse::<usize>().map_err(|_| format!("Invalid number of lines: {}", max_lines))?),
+        None => None,
+    };
+    let lines = get_optimal_lines(&solver, board, &options)?;
+    for line in lines.iter() {
+        println!("{}", line.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" "));
+    }
+    println!("{} optimal lines", lines.len());
+    Ok(())
+}
+
+fn mcts(matches: &ArgMatches) -> Result<(), String> {
+    let board = get_position(matches)?;
+    let mut options = MctsOptions::new();
+    if let Some(iterations) = matches.value_of("Iterations") {
+        options.iterations = iterations.parse::<usize>().map_err(|_| format!("Invalid number of iterations: {}", iterations))?;
+    }
+    if let Some(exploration) = matches.value_of("Exploration") {
+        options.exploration = exploration.parse::<f64>().map_err(|_| format!("Invalid exploration constant: {}", exploration))?;
+    }
+    if let Some(seed) = matches.value_of("Seed") {
+        options.seed = seed.parse::<u64>().map_err(|_| format!("Invalid seed: {}", seed))?;
+    }
+    options.rollout = match matches.value_of("Rollout") {
+        None | Some("random") => RolloutPolicy::Random,
+        Some("wins-first") => RolloutPolicy::WinsFirst,
+        Some(rollout) => return Err(format!("Unknown rollout policy: {}", rollout)),
+    };
+    let report = Mcts::search(board, options)?;
+    let best_move = report.get_best_move().ok_or("No moves were searched.".to_string())?;
+    let solver_moves = Solver::from_board(board).get_next_moves()?;
+    println!("{}\n\n{}\n", board.to_string(), report);
+    println!(
+        "Best move: {} ({} the solver's best moves: {})",
+        best_move.to_string(),
+        if solver_moves.contains(&best_move) { "one of" } else { "not one of" },
+        solver_moves.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" "),
+    );
+    Ok(())
+}
+
+fn get_position(matches: &ArgMatches) -> Result<Board, String> {
+    Board::from_position(matches.value_of("Position").ok_or("Needs a Position!")?)
+}
+
+fn get_depth(matches: &ArgMatches) -> Result<Option<usize>, String> {
+    match matches.value_of("Depth") {
+        Some(depth) => Ok(Some(depth.parse::<usize>().map_err(|_| format!("Invalid depth: {}", depth))?)),
+        None => Ok(None),
+    }
+}
+
+fn get_limits(matches: &ArgMatches) -> Result<SearchLimits, String> {
+    let mut limits = SearchLimits::new();
+    if let Some(time) = matches.value_of("Time") {
+        limits.time = Some(Duration::from_millis(time.parse::<u64>().map_err(|_| format!("Invalid time: {}", time))?));
+    }
+    if let Some(nodes) = matches.value_of("Nodes") {
+        limits.nodes = Some(nodes.parse::<usize>().map_err(|_| format!("Invalid number of nodes: {}", nodes))?);
+    }
+    limits.depth = get_depth(matches)?;
+    Ok(limits)
+}
+
+fn get_evaluator(matches: &ArgMatches) -> Result<Box<dyn Evaluator<Board>>, String> {
+    match matches.value_of("Evaluator") {
+        None | Some("lines") => Ok(Box::new(LineCounting::default())),
+        Some("flat") => Ok(Box::new(Flat)),
+        Some(evaluator) => Err(format!("Unknown evaluator: {}", evaluator)),
+    }
+}
+
+fn get_threads(matches: &ArgMatches) -> Result<usize, String> {
+    match matches.value_of("Threads") {
+        Some(threads) => match threads.parse::<usize>() {
+            Ok(threads) if threads > 0 => Ok(threads),
+            _ => Err(format!("Invalid number of threads: {}", threads)),
+        },
+        None => Ok(1),
+    }
+}
+
+fn open_table(matches: &ArgMatches, path: &str) -> Result<Table, String> {
+    match matches.is_present("Read Table") {
+        true => Table::load(path),
+        false => Table::map(path),
+    }
+}
+
+fn open_output(matches: &ArgMatches) -> Result<Box<dyn Write>, String> {
+    match matches.value_of("Output") {
+        Some(path) => Ok(Box::new(io::BufWriter::new(File::create(path).map_err(|error| error.to_string())?))),
+        None => Ok(Box::new(io::BufWriter::new(io::stdout().lock()))),
+    }
+}
+
 #[cfg(test)]
 mod test_integration_tests {
     use crate::solver::solver::Evaluation;
@@ -113,7 +706,7 @@ mod test_integration_tests {
                 ___",
             ).unwrap()
         );
-        assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(0, 1), Move::new(1, 1)]));
+        assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(0, 1)]));
 
         let mut solver = Solver::from_board(
             Board::from_position(
@@ -122,7 +715,7 @@ mod test_integration_tests {
                 ___",
             ).unwrap()
         );
-        assert_eq!(solver.get_next_moves().unwrap().len(), 6);
+        assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(2, 2)]));
 
         let mut solver = Solver::from_board(
             Board::from_position(
@@ -131,7 +724,7 @@ mod test_integration_tests {
                 __O",
             ).unwrap()
         );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(2, 0)], Evaluation::new(1.))));
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(2, 0)], Evaluation::Win(3))));
 
         let mut solver = Solver::from_board(
             Board::from_position(
@@ -140,7 +733,7 @@ mod test_integration_tests {
                 ___",
             ).unwrap()
         );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(1, 1), Move::new(2, 0)], Evaluation::new(1.))));
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(1, 1), Move::new(2, 0)], Evaluation::Win(5))));
 
         let mut solver = Solver::from_board(
             Board::from_position(
@@ -149,7 +742,7 @@ mod test_integration_tests {
                 __X",
             ).unwrap()
         );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 2), Move::new(2, 1)], Evaluation::new(-1.))));
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(2, 1)], Evaluation::Loss(1))));
 
         let mut solver = Solver::from_board(
             Board::from_position(
@@ -158,7 +751,7 @@ mod test_integration_tests {
                 XXO",
             ).unwrap()
         );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(0, 2), Move::new(1, 1), Move::new(1, 2)], Evaluation::new(0.))));
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(0, 2), Move::new(1, 1), Move::new(1, 2)], Evaluation::Draw)));
 
     }
 
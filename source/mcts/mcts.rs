@@ -0,0 +1,293 @@
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::board::board::Board;
+use crate::game::game::{Game, Outcome, Player};
+
+// How moves are picked when playing a game out at random from a new node.
+pub enum RolloutPolicy {
+    Random,
+    // Takes a move that wins on the spot whenever there is one, and otherwise picks at random.
+    WinsFirst,
+}
+
+pub struct MctsOptions {
+    pub iterations: usize,
+    // How much a move that's been tried less often is favoured over one that's scored better so far.
+    pub exploration: f64,
+    // The same seed always gives the same search.
+    pub seed: u64,
+    pub rollout: RolloutPolicy,
+}
+
+impl MctsOptions {
+
+    pub fn new() -> Self {
+        MctsOptions { iterations: 10000, exploration: 2f64.sqrt(), seed: 0, rollout: RolloutPolicy::Random }
+    }
+
+}
+
+// The statistics of one root move, from the point of view of the player making it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveStatistics<G: Game> {
+    pub m: G::Move,
+    pub visits: usize,
+    pub wins: usize,
+    pub draws: usize,
+}
+
+impl<G: Game> MoveStatistics<G> {
+
+    pub fn get_win_rate(&self) -> f64 {
+        self.wins as f64 / self.visits.max(1) as f64
+    }
+
+    pub fn get_draw_rate(&self) -> f64 {
+        self.draws as f64 / self.visits.max(1) as f64
+    }
+
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsReport<G: Game> {
+    pub iterations: usize,
+    // Every root move, in the order of its moves.
+    pub moves: Vec<MoveStatistics<G>>,
+}
+
+impl<G: Game> MctsReport<G> {
+
+    // The most visited move, which is the one the search trusts most.
+    pub fn get_best_move(&self) -> Option<G::Move> {
+        self.moves.iter()
+            .fold(None, |best: Option<&MoveStatistics<G>>, statistics| match best {
+                Some(best) if best.visits >= statistics.visits => Some(best),
+                _ => Some(statistics),
+            })
+            .map(|statistics| statistics.m)
+    }
+
+}
+
+impl fmt::Display for MctsReport<Board> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = vec![format!("{:<8}{:<10}{:<10}{}", "Move", "Visits", "Win rate", "Draw rate")];
+        for statistics in self.moves.iter() {
+            rows.push(format!(
+                "{:<8}{:<10}{:<10.3}{:.3}",
+                statistics.m.to_string(),
+                statistics.visits,
+                statistics.get_win_rate(),
+                statistics.get_draw_rate(),
+            ));
+        }
+        write!(f, "{}", rows.join("\n"))
+    }
+
+}
+
+struct MctsNode<G: Game> {
+    position: G,
+    parent: Option<usize>,
+    // The move into this node and the player who made it, or `None` at the root.
+    m: Option<(G::Move, Player)>,
+    children: Vec<usize>,
+    untried: Vec<G::Move>,
+    visits: usize,
+    wins: usize,
+    draws: usize,
+}
+
+impl<G: Game> MctsNode<G> {
+
+    fn new(position: G, parent: Option<usize>, m: Option<(G::Move, Player)>) -> Self {
+        let untried = match position.get_outcome() {
+            Outcome::InProgress => position.get_legal_moves(),
+            _ => Vec::new(),
+        };
+        MctsNode { position, parent, m, children: Vec::new(), untried, visits: 0, wins: 0, draws: 0 }
+    }
+
+    // Wins count in full and draws by half, which is what the search maximises.
+    fn get_score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.) / self.visits as f64
+    }
+
+}
+
+// Monte Carlo tree search with UCT: each iteration walks down the tree to a node with moves left to try, adds one
+// of them, plays the game out from there and counts the result on every node along the way.
+pub struct Mcts<G: Game> {
+    nodes: Vec<MctsNode<G>>,
+    options: MctsOptions,
+    rng: StdRng,
+}
+
+impl<G: Game> Mcts<G> {
+
+    pub fn new(position: G, options: MctsOptions) -> Self {
+        let rng = StdRng::seed_from_u64(options.seed);
+        Mcts { nodes: vec![MctsNode::new(position, None, None)], options, rng }
+    }
+
+    pub fn search(position: G, options: MctsOptions) -> Result<MctsReport<G>, String> {
+        let mut mcts = Mcts::new(position, options);
+        mcts.run()?;
+        Ok(mcts.get_report())
+    }
+
+    pub fn run(&mut self) -> Result<(), String> {
+        if self.nodes[0].untried.is_empty() {
+            return Err("The game is already over.".to_string());
+        }
+        for _ in 0..self.options.iterations {
+            let leaf = self.expand(self.select());
+            let winner = self.roll_out(self.nodes[leaf].position);
+            self.back_propagate(leaf, winner);
+        }
+        Ok(())
+    }
+
+    pub fn get_report(&self) -> MctsReport<G> {
+        let mut moves: Vec<MoveStatistics<G>> = self.nodes[0].children.iter()
+            .map(|child| {
+                let node = &self.nodes[*child];
+                MoveStatistics { m: node.m.unwrap().0, visits: node.visits, wins: node.wins, draws: node.draws }
+            })
+            .collect();
+        moves.sort_by_key(|statistics| statistics.m);
+        MctsReport { iterations: self.nodes[0].visits, moves }
+    }
+
+    fn select(&self) -> usize {
+        let mut id = 0;
+        while self.nodes[id].untried.is_empty() && !self.nodes[id].children.is_empty() {
+            let log_visits = (self.nodes[id].visits as f64).ln();
+            id = *self.nodes[id].children.iter()
+                .map(|child| {
+                    let node = &self.nodes[*child];
+                    (child, node.get_score() + self.options.exploration * (log_visits / node.visits as f64).sqrt())
+                })
+                .fold(None, |best: Option<(&usize, f64)>, (child, uct)| match best {
+                    Some((_, best_uct)) if best_uct >= uct => best,
+                    _ => Some((child, uct)),
+                })
+                .expect("The node has children.")
+                .0;
+        }
+        id
+    }
+
+    fn expand(&mut self, id: usize) -> usize {
+        if self.nodes[id].untried.is_empty() {
+            return id;
+        }
+        let index = self.rng.gen_range(0..self.nodes[id].untried.len());
+        let m = self.nodes[id].untried.swap_remove(index);
+        let position = self.nodes[id].position;
+        let player = position.get_active_player().expect("A position with moves left has a player to move.");
+        let child = MctsNode::new(position.apply(m).expect("Untried moves are legal."), Some(id), Some((m, player)));
+        self.nodes.push(child);
+        let child_id = self.nodes.len() - 1;
+        self.nodes[id].children.push(child_id);
+        child_id
+    }
+
+    // Plays the game out from `position` and gives the winner, or `None` for a draw.
+    fn roll_out(&mut self, mut position: G) -> Option<Player> {
+        loop {
+            let moves = match position.get_outcome() {
+                Outcome::InProgress => position.get_legal_moves(),
+                Outcome::Victory(player) => return Some(player),
+                Outcome::Draw | Outcome::Ambiguous => return None,
+            };
+            if moves.is_empty() {
+                return None;
+            }
+            let m = match &self.options.rollout {
+                RolloutPolicy::Random => moves[self.rng.gen_range(0..moves.len())],
+                RolloutPolicy::WinsFirst => {
+                    let player = position.get_active_player().expect("A position with moves left has a player to move.");
+                    let winning = moves.iter().find(|m| {
+                        position.apply(**m).is_ok_and(|child| child.get_outcome() == Outcome::Victory(player))
+                    });
+                    match winning {
+                        Some(m) => *m,
+                        None => moves[self.rng.gen_range(0..moves.len())],
+                    }
+                },
+            };
+            position = position.apply(m).expect("Rollout moves are legal.");
+        }
+    }
+
+    fn back_propagate(&mut self, leaf: usize, winner: Option<Player>) {
+        let mut id = Some(leaf);
+        while let Some(current) = id {
+            let node = &mut self.nodes[current];
+            node.visits += 1;
+            match (node.m, winner) {
+                (_, None) => node.draws += 1,
+                (Some((_, player)), Some(winner)) if player == winner => node.wins += 1,
+                _ => (),
+            }
+            id = node.parent;
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test_mcts {
+    use super::*;
+    use crate::board::board::Move;
+    use crate::solver::solver::Solver;
+
+    #[test]
+    fn test_mcts_finds_the_solvers_moves() {
+        let positions = [
+            // X has to block at (2, 2).
+            "XX_
+            _O_
+            __O",
+            // X wins at once at (0, 2).
+            "XX_
+            OO_
+            ___",
+            // O has to take the centre against a corner.
+            "X________",
+        ];
+        for position in positions {
+            let board = Board::from_position(position).unwrap();
+            let mut options = MctsOptions::new();
+            options.iterations = 5000;
+            let report = Mcts::search(board, options).unwrap();
+            assert_eq!(report.iterations, 5000);
+            assert_eq!(report.moves.iter().map(|statistics| statistics.visits).sum::<usize>(), 5000);
+            assert_eq!(report.moves.iter().map(|statistics| statistics.m).collect::<Vec<Move>>(), board.get_legal_moves());
+            let best_move = report.get_best_move().unwrap();
+            assert!(Solver::from_board(board).get_next_moves().unwrap().contains(&best_move), "{} on\n{}", best_move.to_string(), board.to_string());
+        }
+    }
+
+    #[test]
+    fn test_mcts_is_reproducible() {
+        let board = Board::from_position("X___O____").unwrap();
+        let search = |seed: u64, rollout: RolloutPolicy| {
+            let mut options = MctsOptions::new();
+            options.iterations = 500;
+            options.seed = seed;
+            options.rollout = rollout;
+            Mcts::search(board, options).unwrap()
+        };
+        assert_eq!(search(1, RolloutPolicy::Random), search(1, RolloutPolicy::Random));
+        assert_ne!(search(1, RolloutPolicy::Random), search(2, RolloutPolicy::Random));
+        assert_eq!(search(1, RolloutPolicy::WinsFirst), search(1, RolloutPolicy::WinsFirst));
+
+        assert!(Mcts::search(Board::from_position("XXXOO____").unwrap(), MctsOptions::new()).is_err());
+    }
+}
\ No newline at end of file
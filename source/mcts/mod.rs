@@ -0,0 +1 @@
+pub mod mcts;
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod search;
\ No newline at end of file
@@ -0,0 +1,218 @@
+use crate::board::board::{Board, Move, Square};
+use crate::game::game::{Outcome, Player};
+use crate::solver::solver::{Evaluation, Solver};
+use crate::tree::tree::Tree;
+
+// What the solver makes of a position, without the detail of the score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    XWins,
+    Draw,
+    OWins,
+}
+
+impl Verdict {
+
+    pub fn of(evaluation: Evaluation) -> Verdict {
+        if evaluation.get_score() > 0. {
+            Verdict::XWins
+        } else if evaluation.get_score() < 0. {
+            Verdict::OWins
+        } else {
+            Verdict::Draw
+        }
+    }
+
+    fn is_loss_for(&self, player: Player) -> bool {
+        match player {
+            Player::X => *self == Verdict::OWins,
+            Player::O => *self == Verdict::XWins,
+        }
+    }
+
+}
+
+pub enum Predicate {
+    // One character per square: X, O, _ for empty or ? for anything.
+    Pattern(String),
+    ToMove(Option<Player>),
+    Outcome(Outcome),
+    Evaluation(Verdict),
+    // The player has two or more squares that would each complete a line.
+    Fork(Player),
+    // Every one of the best moves is on this kind of square.
+    BestMoves(Square),
+    BestMoveCount(usize),
+    // Every move that doesn't lose for the side to move is on this kind of square.
+    NonLosingMoves(Square),
+    NonLosingMoveCount(usize),
+}
+
+impl Predicate {
+
+    pub fn matches(&self, board: Board, solver: &Solver) -> bool {
+        match self {
+            Predicate::Pattern(pattern) => {
+                board.to_string().chars().filter(|c| !c.is_whitespace())
+                    .zip(pattern.chars())
+                    .all(|(square, wanted)| wanted == '?' || wanted == square)
+            },
+            Predicate::ToMove(player) => board.get_active_player() == *player,
+            Predicate::Outcome(outcome) => board.get_outcome() == *outcome,
+            Predicate::Evaluation(verdict) => Verdict::of(solver.get_evaluation_and_line_for(board).unwrap().0) == *verdict,
+            Predicate::Fork(player) => board.get_winning_moves(*player).len() >= 2,
+            Predicate::BestMoves(square) => {
+                let best_moves = Predicate::best_moves(board, solver);
+                !best_moves.is_empty() && best_moves.iter().all(|m| Square::of(*m) == *square)
+            },
+            Predicate::BestMoveCount(count) => Predicate::best_moves(board, solver).len() == *count,
+            Predicate::NonLosingMoves(square) => {
+                let moves = Predicate::non_losing_moves(board, solver);
+                !moves.is_empty() && moves.iter().all(|m| Square::of(*m) == *square)
+            },
+            Predicate::NonLosingMoveCount(count) => Predicate::non_losing_moves(board, solver).len() == *count,
+        }
+    }
+
+    fn best_moves(board: Board, solver: &Solver) -> Vec<Move> {
+        solver.get_next_moves_and_evaluation_for(board).map_or(Vec::new(), |(best_moves, _)| best_moves)
+    }
+
+    fn non_losing_moves(board: Board, solver: &Solver) -> Vec<Move> {
+        let player = match board.get_active_player() {
+            Some(player) => player,
+            None => return Vec::new(),
+        };
+        board.get_legal_moves().into_iter()
+            .filter(|m| {
+                let child = board.with_move_made(player, *m).unwrap();
+                !Verdict::of(solver.get_evaluation_and_line_for(child).unwrap().0).is_loss_for(player)
+            })
+            .collect()
+    }
+
+    // Parses a single `name=value` term of a query.
+    pub fn from_string(term: &str) -> Result<Predicate, String> {
+        let (name, value) = term.split_once('=').ok_or(format!("Expected name=value, got: {}", term))?;
+        let player = |value: &str| match value {
+            "X" | "x" => Ok(Player::X),
+            "O" | "o" => Ok(Player::O),
+            _ => Err(format!("Invalid player: {}", value)),
+        };
+        let count = |value: &str| value.parse::<usize>().map_err(|_| format!("Invalid count: {}", value));
+        match name {
+            "pattern" => {
+                let pattern = value.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+                if pattern.len() != 9 || !pattern.chars().all(|c| "XO_?".contains(c)) {
+                    return Err(format!("Invalid pattern: {}", value));
+                }
+                Ok(Predicate::Pattern(pattern))
+            },
+            "to-move" => match value {
+                "none" => Ok(Predicate::ToMove(None)),
+                _ => Ok(Predicate::ToMove(Some(player(value)?))),
+            },
+            "outcome" => match value {
+                "x-wins" => Ok(Predicate::Outcome(Outcome::Victory(Player::X))),
+                "o-wins" => Ok(Predicate::Outcome(Outcome::Victory(Player::O))),
+                "draw" => Ok(Predicate::Outcome(Outcome::Draw)),
+                "in-progress" => Ok(Predicate::Outcome(Outcome::InProgress)),
+                _ => Err(format!("Invalid outcome: {}", value)),
+            },
+            "eval" => match value {
+                "x-wins" => Ok(Predicate::Evaluation(Verdict::XWins)),
+                "o-wins" => Ok(Predicate::Evaluation(Verdict::OWins)),
+                "draw" => Ok(Predicate::Evaluation(Verdict::Draw)),
+                _ => Err(format!("Invalid evaluation: {}", value)),
+            },
+            "fork" => Ok(Predicate::Fork(player(value)?)),
+            "best" => Ok(Predicate::BestMoves(Square::from_string(value)?)),
+            "best-count" => Ok(Predicate::BestMoveCount(count(value)?)),
+            "non-losing" => Ok(Predicate::NonLosingMoves(Square::from_string(value)?)),
+            "non-losing-count" => Ok(Predicate::NonLosingMoveCount(count(value)?)),
+            _ => Err(format!("Unknown query term: {}", name)),
+        }
+    }
+
+}
+
+// A position matches a query when it matches every one of its predicates.
+pub struct Query {
+    predicates: Vec<Predicate>,
+}
+
+impl Query {
+
+    // Queries are whitespace separated terms, e.g. "fork=X to-move=O".
+    pub fn from_string(query: &str) -> Result<Self, String> {
+        let predicates = query.split_whitespace()
+            .map(Predicate::from_string)
+            .collect::<Result<Vec<Predicate>, String>>()?;
+        Ok(Query { predicates })
+    }
+
+    pub fn matches(&self, board: Board, solver: &Solver) -> bool {
+        self.predicates.iter().all(|predicate| predicate.matches(board, solver))
+    }
+
+    // Every position reachable from `root` that matches, with fewer pieces first.
+    // With `canonical_only`, only one position from each symmetry class is considered.
+    pub fn search(&self, root: Board, canonical_only: bool) -> Vec<Board> {
+        let solver = Solver::from_board(root);
+        let tree = match canonical_only {
+            true => Tree::from_board_up_to_symmetry(root),
+            false => Tree::from_board(root),
+        };
+        let mut matches = tree.get_positions().into_iter()
+            .filter(|board| self.matches(*board, &solver))
+            .collect::<Vec<Board>>();
+        matches.sort_by_key(|board| (9 - board.get_legal_moves().len(), board.key()));
+        matches
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_search {
+    use super::*;
+
+    #[test]
+    fn test_query_parses() {
+        assert!(Query::from_string("fork=X to-move=O").is_ok());
+        assert!(Query::from_string("pattern=X???O???_ eval=draw best-count=2").is_ok());
+        assert!(Query::from_string("fork=Z").is_err());
+        assert!(Query::from_string("pattern=XO").is_err());
+        assert!(Query::from_string("forks").is_err());
+    }
+
+    #[test]
+    fn test_search_finds_positions() {
+        let finished = Query::from_string("outcome=draw").unwrap().search(Board::empty(), false);
+        assert_eq!(finished.len(), 16);
+        assert_eq!(Query::from_string("outcome=draw").unwrap().search(Board::empty(), true).len(), 3);
+
+        let corner_openings = Query::from_string("pattern=X???????? to-move=O").unwrap().search(Board::empty(), false);
+        assert_eq!(corner_openings[0], Board::from_position("X________").unwrap());
+        assert!(corner_openings.iter().all(|board| board.to_string().starts_with('X')));
+
+        let forks = Query::from_string("fork=X to-move=O").unwrap().search(Board::empty(), false);
+        assert!(!forks.is_empty());
+        // Unless O can win straight away, a fork wins for X.
+        let solver = Solver::from_board(Board::empty());
+        for board in forks.iter().filter(|board| board.get_winning_moves(Player::O).is_empty()) {
+            assert_eq!(board.get_active_player(), Some(Player::O));
+            assert_eq!(Verdict::of(solver.get_evaluation_and_line_for(*board).unwrap().0), Verdict::XWins);
+        }
+    }
+
+    #[test]
+    fn test_search_with_solver_predicates() {
+        // After a corner opening, the centre is the only reply that doesn't lose.
+        let query = Query::from_string("pattern=X________ to-move=O non-losing-count=1 non-losing=centre").unwrap();
+        assert_eq!(query.search(Board::empty(), false), vec![Board::from_position("X________").unwrap()]);
+
+        let edge_saves = Query::from_string("non-losing=edge non-losing-count=1").unwrap().search(Board::empty(), true);
+        assert!(!edge_saves.is_empty());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,135 @@
+use std::cmp::Reverse;
+use std::fmt;
+
+use crate::board::board::{Board, Move};
+use crate::game::game::Player;
+use crate::board::tactics::{Tactic, get_tactics, tactics_to_string};
+use crate::solver::solver::{Evaluation, Solver};
+
+// One legal move with its exact evaluation, as seen from the position before it is played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveAnalysis {
+    pub m: Move,
+    pub evaluation: Evaluation,
+    // 1 for the best moves; moves with equal evaluations share a rank.
+    pub rank: usize,
+    // The reply on the solver's line after this move, or `None` if the move ends the game.
+    pub best_reply: Option<Move>,
+    pub tactics: Vec<Tactic>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub board: Board,
+    // Every legal move, best first for the player to move, and in row-major order among equals.
+    pub moves: Vec<MoveAnalysis>,
+}
+
+impl Analysis {
+
+    pub fn for_board(solver: &Solver, board: Board) -> Result<Self, String> {
+        let player = board.get_active_player().ok_or("The game is already over.".to_string())?;
+        let mut moves = Vec::new();
+        for m in board.get_legal_moves() {
+            let (evaluation, line) = solver.get_evaluation_and_line_for(board.with_move_made(player, m)?)?;
+            let tactics = get_tactics(&board, m)?;
+            moves.push(MoveAnalysis { m, evaluation: evaluation.next_ply(), rank: 0, best_reply: line.first().copied(), tactics });
+        }
+        match player {
+            Player::X => moves.sort_by_key(|analysis| Reverse(analysis.evaluation)),
+            Player::O => moves.sort_by_key(|analysis| analysis.evaluation),
+        }
+        let mut rank = 0;
+        let mut previous = None;
+        for analysis in moves.iter_mut() {
+            if previous != Some(analysis.evaluation) {
+                rank += 1;
+                previous = Some(analysis.evaluation);
+            }
+            analysis.rank = rank;
+        }
+        Ok(Analysis { board, moves })
+    }
+
+    pub fn get_best_moves(&self) -> Vec<Move> {
+        let mut best_moves: Vec<Move> = self.moves.iter().filter(|analysis| analysis.rank == 1).map(|analysis| analysis.m).collect();
+        best_moves.sort_by_key(|m| (m.get_row(), m.get_column()));
+        best_moves
+    }
+
+}
+
+impl fmt::Display for Analysis {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = vec![format!("{:<6}{:<8}{:<14}{:<12}{}", "Rank", "Move", "Evaluation", "Best reply", "Tactics")];
+        for analysis in self.moves.iter() {
+            let best_reply = match analysis.best_reply {
+                Some(m) => m.to_string(),
+                None => "-".to_string(),
+            };
+            rows.push(format!(
+                "{:<6}{:<8}{:<14}{:<12}{}",
+                analysis.rank,
+                analysis.m.to_string(),
+                analysis.evaluation.to_string(),
+                best_reply,
+                tactics_to_string(&analysis.tactics),
+            ).trim_end().to_string());
+        }
+        write!(f, "{}", rows.join("\n"))
+    }
+
+}
+
+#[cfg(test)]
+mod test_analysis {
+    use super::*;
+
+    #[test]
+    fn test_analysis_ranks_every_move() {
+        let board = Board::from_position(
+            "XO_
+            _X_
+            __O"
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let analysis = Analysis::for_board(&solver, board).unwrap();
+
+        assert_eq!(analysis.moves.len(), 5);
+        assert_eq!(analysis.get_best_moves(), solver.get_next_moves().unwrap());
+        assert_eq!(analysis.moves[0].evaluation, solver.get_evaluation());
+        assert_eq!(analysis.moves[0], MoveAnalysis {
+            m: Move::new(1, 0),
+            evaluation: Evaluation::Win(3),
+            rank: 1,
+            best_reply: Some(Move::new(2, 1)),
+            tactics: vec![Tactic::Fork],
+        });
+        assert!(analysis.moves.windows(2).all(|pair| pair[0].evaluation >= pair[1].evaluation));
+        assert!(analysis.moves.windows(2).all(|pair| pair[1].rank == pair[0].rank + (pair[0].evaluation != pair[1].evaluation) as usize));
+    }
+
+    #[test]
+    fn test_analysis_shows_moves_that_end_the_game() {
+        let board = Board::from_position(
+            "XOX
+            _O_
+            __X"
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let analysis = Analysis::for_board(&solver, board).unwrap();
+
+        assert_eq!(analysis.moves[0], MoveAnalysis {
+            m: Move::new(2, 1),
+            evaluation: Evaluation::Loss(1),
+            rank: 1,
+            best_reply: None,
+            tactics: vec![Tactic::Win],
+        });
+        assert!(analysis.moves[1..].iter().all(|analysis| analysis.evaluation > Evaluation::Loss(1)));
+        assert!(analysis.to_string().starts_with("Rank  Move    Evaluation    Best reply  Tactics\n1     (2, 1)  O wins in 1   -           win\n"));
+
+        assert!(Analysis::for_board(&solver, Board::from_position("XXXOO____").unwrap()).is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::game::game::{Evaluator, Game, Outcome, Player};
+use crate::solver::solver::{Evaluation, Solver, HIGHEST, LOWEST};
+
+// Limits on an iterative deepening search. Whichever runs out first ends it; with none at all it runs until the
+// position is solved.
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    pub time: Option<Duration>,
+    pub nodes: Option<usize>,
+    pub depth: Option<usize>,
+}
+
+impl SearchLimits {
+
+    pub fn new() -> Self {
+        SearchLimits { time: None, nodes: None, depth: None }
+    }
+
+}
+
+// The answer of the last iteration that finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult<G: Game> {
+    pub best_move: G::Move,
+    pub evaluation: Evaluation,
+    // How many plies ahead the last finished iteration looked.
+    pub depth: usize,
+    // Whether the evaluation is what best play really gives, rather than the best that could be seen in time.
+    pub proven: bool,
+    pub nodes_searched: usize,
+}
+
+impl<G: Game> fmt::Display for SearchResult<G> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, depth {}, {} nodes)",
+            self.evaluation.to_string(),
+            if self.proven { "proven" } else { "heuristic" },
+            self.depth,
+            self.nodes_searched,
+        )
+    }
+
+}
+
+// Searches one ply deeper each time, until the result is proven or a limit runs out. Positions the current
+// iteration stops at are scored by the evaluator.
+pub struct Deepening<'a, G: Game> {
+    limits: &'a SearchLimits,
+    evaluator: &'a dyn Evaluator<G>,
+    start: Instant,
+    nodes_searched: usize,
+    // Whether the current iteration stopped anywhere short of the end of the game.
+    reached_horizon: bool,
+}
+
+impl<'a, G: Game> Deepening<'a, G> {
+
+    pub fn new(limits: &'a SearchLimits, evaluator: &'a dyn Evaluator<G>) -> Self {
+        Deepening { limits, evaluator, start: Instant::now(), nodes_searched: 0, reached_horizon: false }
+    }
+
+    pub fn search(&mut self, position: G) -> Result<SearchResult<G>, String> {
+        if position.get_outcome() != Outcome::InProgress || position.get_legal_moves().is_empty() {
+            return Err("The game is already over.".to_string());
+        }
+        let mut result: Option<SearchResult<G>> = None;
+        for depth in 1..=self.limits.depth.unwrap_or(usize::MAX) {
+            self.reached_horizon = false;
+            let previous_best = result.as_ref().map(|result| result.best_move);
+            let (best_move, evaluation) = match self.search_root(position, depth, previous_best) {
+                Some(answer) => answer,
+                None => break,
+            };
+            // A win or loss found within the horizon can't be changed by anything past it: every guess there
+            // ranks above any loss and below any win, so the side that would rather avoid it already prefers them.
+            let proven = !self.reached_horizon || matches!(evaluation, Evaluation::Win(_) | Evaluation::Loss(_));
+            result = Some(SearchResult { best_move, evaluation, depth, proven, nodes_searched: self.nodes_searched });
+            if proven {
+                break;
+            }
+        }
+        result.ok_or("Ran out of search budget before the first iteration finished.".to_string())
+    }
+
+    fn out_of_budget(&self) -> bool {
+        self.limits.nodes.is_some_and(|nodes| self.nodes_searched >= nodes)
+            || self.limits.time.is_some_and(|time| self.start.elapsed() >= time)
+    }
+
+    // The best move from the previous iteration goes first, since it is the most likely to still be best.
+    fn search_root(&mut self, position: G, depth: usize, previous_best: Option<G::Move>) -> Option<(G::Move, Evaluation)> {
+        let player = position.get_active_player()?;
+        let mut moves = position.get_legal_moves();
+        position.order_moves(&mut moves);
+        if let Some(index) = previous_best.and_then(|best| moves.iter().position(|m| *m == best)) {
+            let best = moves.remove(index);
+            moves.insert(0, best);
+        }
+        self.nodes_searched += 1;
+        let (mut alpha, mut beta) = (LOWEST, HIGHEST);
+        let mut best: Option<(G::Move, Evaluation)> = None;
+        for m in moves {
+            let child = position.apply(m).expect("Move is legal by definition of get_legal_moves().");
+            let evaluation = self.alpha_beta(child, alpha.previous_ply(), beta.previous_ply(), depth - 1)?.next_ply();
+            let improves = match (player, best) {
+                (_, None) => true,
+                (Player::X, Some((_, best_evaluation))) => evaluation > best_evaluation,
+                (Player::O, Some((_, best_evaluation))) => evaluation < best_evaluation,
+            };
+            if improves {
+                best = Some((m, evaluation));
+                match player {
+                    Player::X => alpha = alpha.max(evaluation),
+                    Player::O => beta = beta.min(evaluation),
+                }
+            }
+        }
+        best
+    }
+
+    // Fail-soft alpha-beta down to `depth` more plies, or `None` once a limit has run out.
+    fn alpha_beta(&mut self, position: G, mut alpha: Evaluation, mut beta: Evaluation, depth: usize) -> Option<Evaluation> {
+        if self.out_of_budget() {
+            return None;
+        }
+        self.nodes_searched += 1;
+
+        let player = match (position.get_active_player(), Solver::<G>::get_evaluation_for_outcome(position.get_outcome())) {
+            (_, Some(evaluation)) => return Some(evaluation),
+            (Some(player), None) => player,
+            (None, None) => panic!("There's no active player even though the game is in progress."),
+        };
+        // A game in progress without any moves can't be searched further either, so it's left to the evaluator too.
+        let mut moves = position.get_legal_moves();
+        if depth == 0 || moves.is_empty() {
+            self.reached_horizon = true;
+            return Some(Evaluation::Heuristic(self.evaluator.evaluate(&position)));
+        }
+
+        position.order_moves(&mut moves);
+        let mut best_evaluation = match player {
+            Player::X => LOWEST,
+            Player::O => HIGHEST,
+        };
+        for m in moves {
+            let child = position.apply(m).expect("Move is legal by definition of get_legal_moves().");
+            let evaluation = self.alpha_beta(child, alpha.previous_ply(), beta.previous_ply(), depth - 1)?.next_ply();
+            match player {
+                Player::X => {
+                    best_evaluation = best_evaluation.max(evaluation);
+                    alpha = alpha.max(best_evaluation);
+                },
+                Player::O => {
+                    best_evaluation = best_evaluation.min(evaluation);
+                    beta = beta.min(best_evaluation);
+                },
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        Some(best_evaluation)
+    }
+
+}
+
+#[cfg(test)]
+mod test_deepening {
+    use super::*;
+    use crate::board::board::{Board, Move};
+    use crate::game::game::Flat;
+
+    #[test]
+    fn test_deepening_without_limits_solves_the_position() {
+        let boards = [
+            Board::empty(),
+            Board::from_position("X___O____").unwrap(),
+            Board::from_position("XO__X___O").unwrap(),
+            Board::from_position("XO_OX____").unwrap(),
+        ];
+        for board in boards {
+            let solver = Solver::from_board(board);
+            let result = solver.search_iteratively(&SearchLimits::new()).unwrap();
+            assert!(result.proven);
+            assert_eq!(result.evaluation, solver.get_evaluation());
+            assert!(solver.get_next_moves().unwrap().contains(&result.best_move));
+        }
+    }
+
+    #[test]
+    fn test_deepening_stops_at_limits() {
+        let board = Board::empty();
+        let mut limits = SearchLimits::new();
+        limits.depth = Some(2);
+        let result = Solver::from_board(board).search_iteratively(&limits).unwrap();
+        assert_eq!((result.depth, result.proven), (2, false));
+        assert!(matches!(result.evaluation, Evaluation::Heuristic(_)));
+        assert_eq!(result.to_string(), format!("{} (heuristic, depth 2, {} nodes)", result.evaluation.to_string(), result.nodes_searched));
+
+        let mut limits = SearchLimits::new();
+        limits.nodes = Some(100);
+        let result = Solver::from_board(board).search_iteratively(&limits).unwrap();
+        assert!(result.nodes_searched <= 100 && !result.proven);
+
+        // The first iteration looks at the empty board and the 9 positions after it.
+        limits.nodes = Some(5);
+        assert!(Solver::from_board(board).search_iteratively(&limits).is_err());
+
+        let mut limits = SearchLimits::new();
+        limits.time = Some(Duration::ZERO);
+        assert!(Solver::from_board(board).search_iteratively(&limits).is_err());
+    }
+
+    struct Corners;
+
+    impl Evaluator<Board> for Corners {
+        fn evaluate(&self, board: &Board) -> i32 {
+            match board.get_legal_moves().contains(&Move::new(0, 0)) {
+                true => 0,
+                false => 1,
+            }
+        }
+    }
+
+    #[test]
+    fn test_deepening_uses_the_evaluator() {
+        let mut limits = SearchLimits::new();
+        limits.depth = Some(1);
+        // The centre is on the most lines.
+        let result = Solver::from_board(Board::empty()).search_iteratively(&limits).unwrap();
+        assert_eq!((result.best_move, result.evaluation), (Move::new(1, 1), Evaluation::Heuristic(4)));
+
+        let result = Deepening::new(&limits, &Corners).search(Board::empty()).unwrap();
+        assert_eq!((result.best_move, result.evaluation), (Move::new(0, 0), Evaluation::Heuristic(1)));
+        let solver = Solver::from_board(Board::empty()).with_evaluator(Box::new(Corners));
+        assert_eq!(solver.search_iteratively(&limits), Ok(result));
+
+        let result = Deepening::new(&limits, &Flat).search(Board::empty()).unwrap();
+        assert_eq!(result.evaluation, Evaluation::Heuristic(0));
+    }
+
+    #[test]
+    fn test_deepening_proves_wins_before_the_end() {
+        let board = Board::from_position(
+            "XX_
+            OO_
+            ___"
+        ).unwrap();
+        let mut limits = SearchLimits::new();
+        limits.depth = Some(1);
+        let result = Solver::from_board(board).search_iteratively(&limits).unwrap();
+        assert_eq!(result.best_move, Move::new(0, 2));
+        assert_eq!((result.evaluation, result.proven), (Evaluation::Win(1), true));
+
+        assert!(Solver::from_board(Board::from_position("XXXOO____").unwrap()).search_iteratively(&limits).is_err());
+    }
+}
\ No newline at end of file
@@ -1 +1,8 @@
-pub mod solver;
\ No newline at end of file
+pub mod solver;
+pub mod transposition;
+pub mod analysis;
+pub mod parallel;
+pub mod deepening;
+pub mod proof;
+pub mod traps;
+pub mod refutation;
\ No newline at end of file
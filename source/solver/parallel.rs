@@ -0,0 +1,206 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use rayon::prelude::*;
+
+use crate::game::game::{Evaluator, Game, Outcome, Player};
+use crate::solver::solver::{Evaluation, Solver, HIGHEST, LOWEST};
+use crate::solver::transposition::TranspositionTable;
+
+// Positions closer to the root than this have their children searched on several threads at once. Deeper down a
+// thread searches a subtree on its own, and whichever thread is idle steals the subtrees still waiting.
+const SPLIT_DEPTH: usize = 3;
+// The table is split so that threads storing different positions rarely wait for each other.
+const N_SHARDS: usize = 64;
+
+// The same fail-soft alpha-beta as `Solver`, run on positions rather than tree nodes so that it can be shared
+// between threads. Every thread reads and writes one table of proven bounds, and guesses with one evaluator.
+pub struct ParallelSearch<'a, G: Game> {
+    shards: Vec<Mutex<TranspositionTable<G>>>,
+    nodes_searched: AtomicUsize,
+    evaluator: &'a dyn Evaluator<G>,
+}
+
+impl<'a, G> ParallelSearch<'a, G> where G: Game + Send + Sync, G::Move: Send + Sync {
+
+    pub fn new(evaluator: &'a dyn Evaluator<G>) -> Self {
+        ParallelSearch {
+            shards: (0..N_SHARDS).map(|_| Mutex::new(TranspositionTable::new())).collect(),
+            nodes_searched: AtomicUsize::new(0),
+            evaluator,
+        }
+    }
+
+    // The exact evaluation of `position`. The root's moves are split between the threads, and each of them is
+    // solved exactly, so the table ends up holding the evaluation of every child of the root as well.
+    pub fn evaluate(&self, position: G, threads: usize) -> Result<Evaluation, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(|error| format!("Couldn't start {} threads: {}", threads, error))?;
+        Ok(pool.install(|| self.alpha_beta(position, LOWEST, HIGHEST, 0)))
+    }
+
+    pub fn n_nodes_searched(&self) -> usize {
+        self.nodes_searched.load(Ordering::Relaxed)
+    }
+
+    // Everything proven during the search, in one table.
+    pub fn into_table(self) -> TranspositionTable<G> {
+        let mut table = TranspositionTable::new();
+        for shard in self.shards {
+            table.merge(shard.into_inner().expect("No thread panicked while holding the table."));
+        }
+        table
+    }
+
+    fn shard(&self, position: G) -> &Mutex<TranspositionTable<G>> {
+        let mut hasher = DefaultHasher::new();
+        position.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % N_SHARDS]
+    }
+
+    fn alpha_beta(&self, position: G, mut alpha: Evaluation, mut beta: Evaluation, depth: usize) -> Evaluation {
+        let (lower, upper) = match self.shard(position).lock().unwrap().get(position) {
+            Some(entry) => (entry.lower, entry.upper),
+            None => (LOWEST, HIGHEST),
+        };
+        if lower == upper || lower >= beta {
+            return lower;
+        }
+        if upper <= alpha {
+            return upper;
+        }
+        if lower > alpha {
+            alpha = lower;
+        }
+        if upper < beta {
+            beta = upper;
+        }
+        self.nodes_searched.fetch_add(1, Ordering::Relaxed);
+
+        let moves = match position.get_outcome() {
+            Outcome::InProgress => position.get_legal_moves(),
+            _ => Vec::new(),
+        };
+        // A game in progress without any moves can't be searched further, so all there is to go on is a guess.
+        let evaluation = match position.get_active_player() {
+            _ if moves.is_empty() => Solver::<G>::get_evaluation_for_outcome(position.get_outcome())
+                .unwrap_or_else(|| Evaluation::Heuristic(self.evaluator.evaluate(&position))),
+            Some(player) => self.search_children(position, moves, player, alpha, beta, depth),
+            None => panic!("There's no active player even though the game is in progress."),
+        };
+
+        // Another thread may have proven something about this position in the meantime, so the bounds are
+        // combined with whatever is there now rather than the ones read at the start.
+        let mut shard = self.shard(position).lock().unwrap();
+        let (lower, upper) = match shard.get(position) {
+            Some(entry) => (entry.lower, entry.upper),
+            None => (LOWEST, HIGHEST),
+        };
+        let (lower, upper) = if evaluation <= alpha {
+            (lower, upper.min(evaluation))
+        } else if evaluation >= beta {
+            (lower.max(evaluation), upper)
+        } else {
+            (evaluation, evaluation)
+        };
+        shard.store_bounds(position, lower, upper);
+        evaluation
+    }
+
+    fn search_children(&self, position: G, mut moves: Vec<G::Move>, player: Player, mut alpha: Evaluation, mut beta: Evaluation, depth: usize) -> Evaluation {
+        position.order_moves(&mut moves);
+        let mut best_evaluation = match player {
+            Player::X => LOWEST,
+            Player::O => HIGHEST,
+        };
+        // At the root every move is searched at once. Nearer the leaves the first move is searched alone, since
+        // it's usually best and the window it leaves makes the others cheaper, and then the rest at once.
+        let n_serial = match depth {
+            0 => 0,
+            _ if depth < SPLIT_DEPTH => 1,
+            _ => moves.len(),
+        };
+        for m in moves[..n_serial].iter() {
+            let evaluation = self.alpha_beta_child(position.apply(*m).expect("Move is legal by definition of get_legal_moves()."), alpha, beta, depth);
+            match player {
+                Player::X => {
+                    best_evaluation = best_evaluation.max(evaluation);
+                    alpha = alpha.max(best_evaluation);
+                },
+                Player::O => {
+                    best_evaluation = best_evaluation.min(evaluation);
+                    beta = beta.min(best_evaluation);
+                },
+            }
+            if alpha >= beta {
+                return best_evaluation;
+            }
+        }
+        // Siblings searched together all get the same window, and the best of their results is still a bound of
+        // the right kind.
+        let evaluations = moves[n_serial..].par_iter()
+            .map(|m| self.alpha_beta_child(position.apply(*m).expect("Move is legal by definition of get_legal_moves()."), alpha, beta, depth))
+            .collect::<Vec<Evaluation>>();
+        match player {
+            Player::X => evaluations.into_iter().fold(best_evaluation, Evaluation::max),
+            Player::O => evaluations.into_iter().fold(best_evaluation, Evaluation::min),
+        }
+    }
+
+    fn alpha_beta_child(&self, child: G, alpha: Evaluation, beta: Evaluation, depth: usize) -> Evaluation {
+        self.alpha_beta(child, alpha.previous_ply(), beta.previous_ply(), depth + 1).next_ply()
+    }
+
+}
+
+#[cfg(test)]
+mod test_parallel {
+    use super::*;
+    use crate::board::board::Board;
+    use crate::game::game::Flat;
+
+    #[test]
+    fn test_parallel_search_agrees_with_solver() {
+        let boards = [
+            Board::empty(),
+            Board::from_position("X___O____").unwrap(),
+            Board::from_position("XO__X___O").unwrap(),
+            Board::from_position("XOX_O____").unwrap(),
+            Board::from_position("XXXOO____").unwrap(),
+        ];
+        for board in boards {
+            let serial = Solver::from_board(board);
+            for threads in [1, 2, 4] {
+                let search = ParallelSearch::new(&Flat);
+                assert_eq!(search.evaluate(board, threads), Ok(serial.get_evaluation()));
+                assert!(search.n_nodes_searched() > 0);
+
+                let parallel = Solver::from_board(board).with_threads(threads).unwrap();
+                let nodes_searched = parallel.n_nodes_searched();
+                assert_eq!(parallel.get_next_moves_and_evaluation(), serial.get_next_moves_and_evaluation());
+                // With more than one thread, the best moves come straight from what the threads solved.
+                if threads > 1 {
+                    assert_eq!(parallel.n_nodes_searched(), nodes_searched);
+                }
+                assert_eq!(parallel.get_evaluation_and_line(), serial.get_evaluation_and_line());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_search_solves_every_root_move() {
+        let board = Board::from_position("X___O____").unwrap();
+        let search = ParallelSearch::new(&Flat);
+        search.evaluate(board, 4).unwrap();
+        let table = search.into_table();
+        for m in board.get_legal_moves() {
+            let child = board.with_move_made(Player::X, m).unwrap();
+            let entry = table.get(child).unwrap();
+            assert_eq!(entry.lower, entry.upper, "{} isn't solved exactly", m.to_string());
+        }
+    }
+}
\ No newline at end of file
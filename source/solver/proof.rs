@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::game::game::{Game, Outcome, Player};
+
+// Stands for a number too large to ever be reached: a node that can't be proven has an infinite proof number.
+const INFINITY: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Proof {
+    Win,
+    // A draw or a loss with best play.
+    NotWin,
+    // The budget ran out first.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofResult {
+    pub proof: Proof,
+    // The least number of unexpanded positions that would still have to be proven to prove the win, and to
+    // disprove it, as far as the search got. One of them is 0 once the search has an answer.
+    pub proof_number: u64,
+    pub disproof_number: u64,
+    // How many positions were expanded, counting a position again each time the search comes back to it.
+    pub nodes: usize,
+}
+
+impl fmt::Display for ProofResult {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let proof = match self.proof {
+            Proof::Win => "Proven win",
+            Proof::NotWin => "Proven not a win",
+            Proof::Unknown => "Unknown",
+        };
+        let number = |n: u64| if n == INFINITY { "inf".to_string() } else { n.to_string() };
+        write!(
+            f,
+            "{} (proof number {}, disproof number {}, {} nodes)",
+            proof,
+            number(self.proof_number),
+            number(self.disproof_number),
+            self.nodes,
+        )
+    }
+
+}
+
+// Depth-first proof-number search (df-pn). Like plain proof-number search, it keeps working on whichever position
+// would go furthest towards settling the root, so a win can be proven without looking at most of the tree. Instead
+// of keeping that tree in memory it searches depth first, staying below a position only while its numbers are under
+// thresholds worked out from its siblings, and keeps the numbers of the positions it has expanded in a table. Each
+// expansion stores at most one entry, so the table never holds more than the budget.
+pub struct ProofNumberSearch<G: Game> {
+    root: G,
+    attacker: Player,
+    // Keyed by canonical position, since symmetric positions take just as much to prove.
+    table: HashMap<G, (u64, u64)>,
+    max_nodes: usize,
+    nodes: usize,
+}
+
+impl<G: Game> ProofNumberSearch<G> {
+
+    // Sets out to prove that the player to move in `position` wins.
+    pub fn new(position: G) -> Result<Self, String> {
+        let attacker = position.get_active_player().ok_or("The game is already over.".to_string())?;
+        Ok(ProofNumberSearch { root: position, attacker, table: HashMap::new(), max_nodes: 0, nodes: 0 })
+    }
+
+    // Gives up once `max_nodes` positions have been expanded in total.
+    pub fn search(&mut self, max_nodes: usize) -> ProofResult {
+        self.max_nodes = max_nodes;
+        self.expand(self.root, INFINITY, INFINITY);
+        let (proof_number, disproof_number) = self.look_up(self.root);
+        let proof = match (proof_number, disproof_number) {
+            (0, _) => Proof::Win,
+            (_, 0) => Proof::NotWin,
+            _ => Proof::Unknown,
+        };
+        ProofResult { proof, proof_number, disproof_number, nodes: self.nodes }
+    }
+
+    // Finished games are settled straight away, and a position that hasn't been expanded yet counts as one
+    // position to prove and one to disprove.
+    fn look_up(&self, position: G) -> (u64, u64) {
+        match position.get_outcome() {
+            Outcome::Victory(player) if player == self.attacker => (0, INFINITY),
+            Outcome::InProgress if !position.get_legal_moves().is_empty() => {
+                self.table.get(&position.canonical().0).copied().unwrap_or((1, 1))
+            },
+            _ => (INFINITY, 0),
+        }
+    }
+
+    // Searches below `position` until its proof number reaches `max_proof` or its disproof number reaches
+    // `max_disproof`, which includes settling it either way, or the budget runs out.
+    fn expand(&mut self, position: G, max_proof: u64, max_disproof: u64) {
+        self.nodes += 1;
+        // Where the attacker moves, one proven child proves the position; where the defender does, one disproven
+        // child disproves it.
+        let attacking = position.get_active_player() == Some(self.attacker);
+        let mut moves = position.get_legal_moves();
+        position.order_moves(&mut moves);
+        let children = moves.into_iter()
+            .map(|m| position.apply(m).expect("Move is legal by definition of get_legal_moves()."))
+            .collect::<Vec<G>>();
+        loop {
+            let numbers = children.iter().map(|child| self.look_up(*child)).collect::<Vec<(u64, u64)>>();
+            let proof_numbers = numbers.iter().map(|(proof_number, _)| *proof_number);
+            let disproof_numbers = numbers.iter().map(|(_, disproof_number)| *disproof_number);
+            let (proof_number, disproof_number) = match attacking {
+                true => (proof_numbers.min().unwrap_or(INFINITY), disproof_numbers.fold(0, u64::saturating_add)),
+                false => (proof_numbers.fold(0, u64::saturating_add), disproof_numbers.min().unwrap_or(INFINITY)),
+            };
+            if proof_number >= max_proof || disproof_number >= max_disproof || self.nodes >= self.max_nodes {
+                self.table.insert(position.canonical().0, (proof_number, disproof_number));
+                return;
+            }
+
+            // The child whose number decides the position's, which stays the one to search until it's no longer
+            // the smallest: until it passes the second smallest, or takes the position over its own threshold.
+            let deciding = |(proof_number, disproof_number): (u64, u64)| match attacking {
+                true => proof_number,
+                false => disproof_number,
+            };
+            let mut order = (0..children.len()).collect::<Vec<usize>>();
+            order.sort_by_key(|index| deciding(numbers[*index]));
+            let (best, (child_proof, child_disproof)) = (order[0], numbers[order[0]]);
+            let second = order.get(1).map_or(INFINITY, |index| deciding(numbers[*index]));
+            let (child_max_proof, child_max_disproof) = match attacking {
+                true => (max_proof.min(second.saturating_add(1)), max_disproof - (disproof_number - child_disproof)),
+                false => (max_proof - (proof_number - child_proof), max_disproof.min(second.saturating_add(1))),
+            };
+            self.expand(children[best], child_max_proof, child_max_disproof);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test_proof {
+    use super::*;
+    use crate::board::board::Board;
+    use crate::solver::solver::{Evaluation, Solver};
+    use crate::tree::tree::Tree;
+
+    #[test]
+    fn test_proof_number_search_agrees_with_solver() {
+        let solver = Solver::from_board(Board::empty());
+        for board in Tree::from_board(Board::empty()).get_positions() {
+            let player = match board.get_active_player() {
+                Some(player) if board.get_legal_moves().len() <= 6 => player,
+                _ => continue,
+            };
+            let result = ProofNumberSearch::new(board).unwrap().search(usize::MAX);
+            let wins = match solver.get_evaluation_and_line_for(board).unwrap().0 {
+                Evaluation::Win(_) => player == Player::X,
+                Evaluation::Loss(_) => player == Player::O,
+                _ => false,
+            };
+            assert_eq!(result.proof, if wins { Proof::Win } else { Proof::NotWin }, "\n{}", board.to_string());
+        }
+    }
+
+    #[test]
+    fn test_proof_number_search_within_a_budget() {
+        let board = Board::from_position("XO__X___O").unwrap();
+        let result = ProofNumberSearch::new(board).unwrap().search(usize::MAX);
+        assert_eq!((result.proof, result.proof_number, result.disproof_number), (Proof::Win, 0, INFINITY));
+        assert!(result.to_string().starts_with("Proven win (proof number 0, disproof number inf, "));
+
+        let result = ProofNumberSearch::new(Board::empty()).unwrap().search(100);
+        assert_eq!(result.proof, Proof::Unknown);
+        assert!(result.proof_number > 0 && result.disproof_number > 0);
+        assert_eq!(result.nodes, 100);
+
+        let mut search = ProofNumberSearch::new(Board::empty()).unwrap();
+        let result = search.search(usize::MAX);
+        assert_eq!(result.proof, Proof::NotWin);
+        assert!(search.table.len() <= result.nodes);
+
+        assert!(ProofNumberSearch::new(Board::from_position("XXXOO____").unwrap()).is_err());
+    }
+}
\ No newline at end of file
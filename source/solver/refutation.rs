@@ -0,0 +1,167 @@
+use std::fmt;
+
+use crate::board::board::{Board, Move};
+use crate::game::game::Player;
+use crate::board::tactics::{Tactic, get_tactics};
+use crate::solver::solver::{Evaluation, Solver};
+
+// Why a move is worse than the best ones: what best play gives after it, and how the opponent gets there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Refutation {
+    pub m: Move,
+    pub player: Player,
+    // What best play gives from the position, and what it gives once `m` is played.
+    pub best_evaluation: Evaluation,
+    pub evaluation: Evaluation,
+    pub is_best: bool,
+    // Best play after `m`, starting with the opponent's reply. Empty when `m` ends the game.
+    pub line: Vec<Move>,
+    pub reply_tactics: Vec<Tactic>,
+}
+
+impl Refutation {
+
+    pub fn for_move(solver: &Solver, board: Board, m: Move) -> Result<Self, String> {
+        let player = board.get_active_player().ok_or("The game is already over.".to_string())?;
+        let (best_moves, best_evaluation) = solver.get_next_moves_and_evaluation_for(board)?;
+        let child = board.with_move_made(player, m)?;
+        let (evaluation, line) = solver.get_evaluation_and_line_for(child)?;
+        let reply_tactics = match line.first() {
+            Some(reply) => get_tactics(&child, *reply)?,
+            None => Vec::new(),
+        };
+        Ok(Refutation {
+            m,
+            player,
+            best_evaluation,
+            evaluation: evaluation.next_ply(),
+            is_best: best_moves.contains(&m),
+            line,
+            reply_tactics,
+        })
+    }
+
+    // How much worse the move is for the player making it.
+    fn get_verdict(&self) -> &'static str {
+        match (for_player(self.evaluation, self.player), for_player(self.best_evaluation, self.player)) {
+            (Evaluation::Loss(_), Evaluation::Loss(_)) => "loses sooner",
+            (Evaluation::Loss(_), _) => "loses",
+            (Evaluation::Draw, _) => "only draws",
+            (Evaluation::Win(_), _) => "wins more slowly",
+            _ => "is worse",
+        }
+    }
+
+}
+
+impl fmt::Display for Refutation {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let summary = match (self.is_best, self.line.first()) {
+            (true, _) => format!("{} is one of the best moves", self.m.to_string()),
+            (false, None) => format!("{} {}: the game ends", self.m.to_string(), self.get_verdict()),
+            (false, Some(reply)) => {
+                let tactics = self.reply_tactics.iter().map(|tactic| get_participle(*tactic)).collect::<Vec<&str>>();
+                let reason = match tactics.split_last() {
+                    None => "".to_string(),
+                    Some((last, [])) => format!(", {}", last),
+                    Some((last, rest)) => format!(", {} and {}", rest.join(", "), last),
+                };
+                format!(
+                    "{} {}: {} replies {}{}",
+                    self.m.to_string(),
+                    self.get_verdict(),
+                    self.player.get_opponent().to_string(),
+                    reply.to_string(),
+                    reason,
+                )
+            },
+        };
+        let line = match self.line.is_empty() {
+            true => "-".to_string(),
+            false => self.line.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" "),
+        };
+        write!(
+            f,
+            "{}\nEvaluation: {} -> {}\nLine: {}",
+            summary,
+            self.best_evaluation.to_string(),
+            self.evaluation.to_string(),
+            line,
+        )
+    }
+
+}
+
+// Turns an evaluation from X's point of view into one from `player`'s.
+fn for_player(evaluation: Evaluation, player: Player) -> Evaluation {
+    match (player, evaluation) {
+        (Player::O, Evaluation::Win(plies)) => Evaluation::Loss(plies),
+        (Player::O, Evaluation::Loss(plies)) => Evaluation::Win(plies),
+        _ => evaluation,
+    }
+}
+
+fn get_participle(tactic: Tactic) -> &'static str {
+    match tactic {
+        Tactic::Win => "winning",
+        Tactic::Block => "blocking",
+        Tactic::Threat => "threatening to win",
+        Tactic::Fork => "creating a fork",
+        Tactic::StopsFork => "stopping a fork",
+    }
+}
+
+#[cfg(test)]
+mod test_refutation {
+    use super::*;
+
+    #[test]
+    fn test_refutation_of_a_losing_move() {
+        let board = Board::from_position(
+            "X__
+            _O_
+            __X"
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let refutation = solver.refute(Move::new(0, 2)).unwrap();
+        assert_eq!((refutation.best_evaluation, refutation.evaluation), (Evaluation::Draw, Evaluation::Win(4)));
+        assert!(!refutation.is_best);
+        assert_eq!(refutation.line.len(), 3);
+        assert_eq!(refutation.line[0], Move::new(2, 0));
+        assert_eq!(refutation.reply_tactics, vec![Tactic::Block, Tactic::Fork]);
+        assert!(refutation.to_string().starts_with(
+            "(0, 2) loses: X replies (2, 0), blocking and creating a fork\nEvaluation: Drawn -> X wins in 4\nLine: (2, 0) "
+        ));
+
+        let refutation = solver.refute(Move::new(0, 1)).unwrap();
+        assert!(refutation.is_best);
+        assert!(refutation.to_string().starts_with("(0, 1) is one of the best moves\nEvaluation: Drawn -> Drawn\n"));
+
+        assert!(solver.refute(Move::new(0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_refutation_of_a_slower_win() {
+        let board = Board::from_position(
+            "XX_
+            O__
+            O__"
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        let refutation = solver.refute(Move::new(1, 1)).unwrap();
+        assert_eq!((refutation.best_evaluation, refutation.evaluation), (Evaluation::Win(1), Evaluation::Win(3)));
+        assert!(refutation.to_string().starts_with("(1, 1) wins more slowly: O replies "));
+
+        let refutation = Solver::from_board(Board::from_position("XX_OO____").unwrap()).refute(Move::new(2, 0)).unwrap();
+        assert_eq!(refutation.to_string(), "(2, 0) loses: O replies (1, 2), winning\nEvaluation: X wins in 1 -> O wins in 2\nLine: (1, 2)");
+
+        let board = Board::from_position(
+            "XOX
+            XOO
+            OX_"
+        ).unwrap();
+        let refutation = Solver::from_board(board).refute(Move::new(2, 2)).unwrap();
+        assert_eq!(refutation.to_string(), "(2, 2) is one of the best moves\nEvaluation: Drawn -> Drawn\nLine: -");
+    }
+}
\ No newline at end of file
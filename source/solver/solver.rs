@@ -1,144 +1,444 @@
-use crate::board::board::{Board, Move, Outcome, Player};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+
+use crate::board::board::{Board, Move};
+use crate::game::game::{Evaluator, Game, Outcome, Player};
+use crate::solver::analysis::Analysis;
+use crate::solver::deepening::{Deepening, SearchLimits, SearchResult};
+use crate::solver::parallel::ParallelSearch;
+use crate::solver::refutation::Refutation;
+use crate::solver::traps::{TrapScore, get_trap_scores};
+use crate::solver::transposition::{Entry, TranspositionTable};
+use crate::table::table::Table;
+use crate::table::tablebase::Tablebase;
 use crate::tree::tree::{Tree, Node};
 
-pub struct Solver {
-    tree: Tree,
+pub struct Solver<G: Game = Board> {
+    source: Source<G>,
+    transpositions: RefCell<TranspositionTable<G>>,
+    nodes_searched: Cell<usize>,
+    evaluator: Box<dyn Evaluator<G>>,
 }
 
-impl Solver {
-    pub fn from_board(board: Board) -> Self {
-        let tree = Tree::from_board(board);
-        Solver { tree }
+// A game already lost or already won, which no other evaluation can be below or above.
+pub const LOWEST: Evaluation = Evaluation::Loss(0);
+pub const HIGHEST: Evaluation = Evaluation::Win(0);
+
+// Where answers come from: either a game tree that gets searched, or a table that was solved ahead of time,
+// read from a file or generated in memory.
+enum Source<G: Game> {
+    Tree(Tree<G>),
+    Table(G, Box<dyn SolvedTable<G>>),
+}
+
+// A table of positions solved ahead of time, giving the evaluation and best moves of each position in it.
+pub trait SolvedTable<G: Game> {
+    fn look_up(&self, position: G) -> Option<(Evaluation, Vec<G::Move>)>;
+}
+
+impl<G: Game> Solver<G> {
+    pub fn from_board(board: G) -> Self {
+        let tree = Tree::lazy(board);
+        Solver {
+            source: Source::Tree(tree),
+            transpositions: RefCell::new(TranspositionTable::new()),
+            nodes_searched: Cell::new(0),
+            evaluator: G::default_evaluator(),
+        }
+    }
+
+    pub fn from_solved_table(board: G, table: Box<dyn SolvedTable<G>>) -> Result<Self, String> {
+        if table.look_up(board).is_none() {
+            return Err(format!("Position is not in the table:\n{}", board.to_string()));
+        }
+        Ok(Solver {
+            source: Source::Table(board, table),
+            transpositions: RefCell::new(TranspositionTable::new()),
+            nodes_searched: Cell::new(0),
+            evaluator: G::default_evaluator(),
+        })
+    }
+
+    // Guesses at the positions a search stops at with `evaluator` rather than the game's default one.
+    pub fn with_evaluator(self, evaluator: Box<dyn Evaluator<G>>) -> Self {
+        Solver { evaluator, ..self }
+    }
+
+    // Keys the transposition table by canonical board, so symmetric positions are only solved once.
+    pub fn sharing_symmetries(self) -> Self {
+        Solver { transpositions: RefCell::new(TranspositionTable::canonical()), ..self }
+    }
+
+    pub fn get_board(&self) -> G {
+        match &self.source {
+            Source::Tree(tree) => tree.get_root().get_board(),
+            Source::Table(board, _) => *board,
+        }
+    }
+
+    // Moves the solver on to the position after `m`, keeping everything already solved below it.
+    pub fn play(&mut self, m: G::Move) -> Result<(), String> {
+        match &mut self.source {
+            Source::Tree(tree) => {
+                tree.advance(m)?;
+                let tree = &*tree;
+                self.transpositions.get_mut().retain(|board| tree.find(board).is_some());
+            },
+            Source::Table(board, _) => {
+                *board = board.apply(m)?;
+            },
+        }
+        Ok(())
     }
 
     pub fn get_evaluation(&self) -> Evaluation {
-        let root = self.tree.get_root();
-        self.get_evaluation_and_line_for_node(root).0
+        self.get_evaluation_and_line().0
     }
 
-    fn get_evaluation_and_line_for_node(&self, node: &Node) -> (Evaluation, Vec<Move>) {
-        let children = node.get_children().iter();
-        if children.len() == 0 {
-            return (Solver::get_evaluation_for_outcome(node.get_board().get_outcome()), Vec::new());
+    // The line follows the last of the best moves at every step, which is the line a plain minimax search finds.
+    fn get_evaluation_and_line_for_node(&self, node: &Node<G>) -> (Evaluation, Vec<G::Move>) {
+        let evaluation = self.alpha_beta(node, LOWEST, HIGHEST);
+        let mut line = Vec::new();
+        let mut current = *node;
+        while let Ok((best_moves, _)) = self.get_next_moves_and_evaluation_for_node(&current) {
+            let m = *best_moves.last().expect("A position in progress always has a best move.");
+            line.push(m);
+            current = current.get_child_by_move(m).expect("Best moves are legal.");
         }
-        let active_player = node.get_active_player();
-        let mut best_evaluation = Evaluation(
-            match active_player {
-                Some(Player::X) => -1.,
-                Some(Player::O) => 1.,
-                None => panic!("There's no active player even though there the node has children."),
-            }
-        );
-        
-        let mut best_move_line: Vec<Move> = Vec::new();
-        for child_move in node.get_legal_moves().iter() {
-            let child = node.get_child(child_move.get_row(), child_move.get_column()).expect("Move is legal by definition of get_legal_moves().");
-            let (child_evaluation, child_line) = self.get_evaluation_and_line_for_node(child);
-            match active_player {
-                Some(Player::X) => {
-                    if child_evaluation >= best_evaluation {
-                        best_evaluation = child_evaluation;
-                        best_move_line = vec![*child_move];
-                        best_move_line.append(&mut child_line.clone());
+        (evaluation, line)
+    }
+
+    // Fail-soft alpha-beta: the result is exact when it lies strictly between alpha and beta, otherwise it is
+    // only a bound on the side of the window it fell out of. Bounds proven so far are kept per position.
+    fn alpha_beta(&self, node: &Node<G>, mut alpha: Evaluation, mut beta: Evaluation) -> Evaluation {
+        let board = node.get_board();
+        let (lower, upper) = match self.transpositions.borrow().get(board) {
+            Some(entry) => (entry.lower, entry.upper),
+            None => (LOWEST, HIGHEST),
+        };
+        if lower == upper || lower >= beta {
+            return lower;
+        }
+        if upper <= alpha {
+            return upper;
+        }
+        if lower > alpha {
+            alpha = lower;
+        }
+        if upper < beta {
+            beta = upper;
+        }
+        self.nodes_searched.set(self.nodes_searched.get() + 1);
+
+        let evaluation = match node.get_active_player() {
+            // A game in progress without any moves can't be searched further, so as in the iterative search, all there
+            // is to go on is a guess.
+            _ if node.n_children() == 0 => Self::get_evaluation_for_outcome(board.get_outcome())
+                .unwrap_or_else(|| Evaluation::Heuristic(self.evaluator.evaluate(&board))),
+            Some(player) => self.search_children(node, player, alpha, beta),
+            None => panic!("There's no active player even though there the node has children."),
+        };
+
+        let (lower, upper) = if evaluation <= alpha {
+            (lower, evaluation)
+        } else if evaluation >= beta {
+            (evaluation, upper)
+        } else {
+            (evaluation, evaluation)
+        };
+        self.transpositions.borrow_mut().store_bounds(board, lower, upper);
+        evaluation
+    }
+
+    fn search_children(&self, node: &Node<G>, player: Player, mut alpha: Evaluation, mut beta: Evaluation) -> Evaluation {
+        let mut best_evaluation = match player {
+            Player::X => LOWEST,
+            Player::O => HIGHEST,
+        };
+        // Moves most likely to be best come first, so that cut-offs happen early.
+        let mut moves = node.get_legal_moves();
+        node.get_board().order_moves(&mut moves);
+        for m in moves {
+            let child = node.get_child_by_move(m).expect("Move is legal by definition of get_legal_moves().");
+            let evaluation = self.alpha_beta_child(&child, alpha, beta);
+            match player {
+                Player::X => {
+                    if evaluation > best_evaluation {
+                        best_evaluation = evaluation;
+                    }
+                    if best_evaluation > alpha {
+                        alpha = best_evaluation;
                     }
                 },
-                Some(Player::O) => {
-                    if child_evaluation <= best_evaluation {
-                        best_evaluation = child_evaluation;
-                        best_move_line = vec![*child_move];
-                        best_move_line.append(&mut child_line.clone());
+                Player::O => {
+                    if evaluation < best_evaluation {
+                        best_evaluation = evaluation;
+                    }
+                    if best_evaluation < beta {
+                        beta = best_evaluation;
                     }
                 },
-                None => panic!("There's no active player even though there the node has children."),
+            }
+            if alpha >= beta {
+                break;
             }
         }
-        return (best_evaluation, best_move_line);
+        best_evaluation
     }
 
-    fn get_evaluation_for_outcome(outcome: Outcome) -> Evaluation {
-        let raw_evaluation = match outcome {
-            Outcome::InProgress => 0.,
-            Outcome::Ambiguous => 0.,
-            Outcome::Draw => 0.,
-            Outcome::Victory(Player::X) => 1.,
-            Outcome::Victory(Player::O) => -1.,
-        };
-        return Evaluation(raw_evaluation);
+    // Searches a child with the window shifted down one ply, and answers as seen from its parent.
+    fn alpha_beta_child(&self, child: &Node<G>, alpha: Evaluation, beta: Evaluation) -> Evaluation {
+        self.alpha_beta(child, alpha.previous_ply(), beta.previous_ply()).next_ply()
     }
 
-    pub fn get_next_moves(&self) -> Result<Vec<Move>, String> {
+    // `None` while the game is still in progress, since only searching further or guessing can say what it's worth.
+    pub fn get_evaluation_for_outcome(outcome: Outcome) -> Option<Evaluation> {
+        match outcome {
+            Outcome::InProgress => None,
+            Outcome::Ambiguous => Some(Evaluation::Draw),
+            Outcome::Draw => Some(Evaluation::Draw),
+            Outcome::Victory(Player::X) => Some(Evaluation::Win(0)),
+            Outcome::Victory(Player::O) => Some(Evaluation::Loss(0)),
+        }
+    }
+
+    pub fn get_next_moves(&self) -> Result<Vec<G::Move>, String> {
         let (next_moves, _) = self.get_next_moves_and_evaluation()?;
         return Ok(next_moves);
     }
 
-    pub fn get_next_moves_and_evaluation(&self) -> Result<(Vec<Move>, Evaluation), String> {
+    pub fn get_next_moves_and_evaluation(&self) -> Result<(Vec<G::Move>, Evaluation), String> {
+        self.get_next_moves_and_evaluation_for(self.get_board())
+    }
+
+    // Answers for any position reachable from the one the solver was built on.
+    pub fn get_next_moves_and_evaluation_for(&self, board: G) -> Result<(Vec<G::Move>, Evaluation), String> {
+        match &self.source {
+            Source::Tree(tree) => self.get_next_moves_and_evaluation_for_node(&Solver::find(tree, board)?),
+            Source::Table(..) => {
+                if board.get_active_player().is_none() {
+                    return Err("The game is already over.".to_string());
+                }
+                let (evaluation, best_moves) = self.look_up(board)?;
+                Ok((best_moves, evaluation))
+            },
+        }
+    }
+
+    pub fn get_evaluation_and_line_for(&self, board: G) -> Result<(Evaluation, Vec<G::Move>), String> {
+        match &self.source {
+            Source::Tree(tree) => Ok(self.get_evaluation_and_line_for_node(&Solver::find(tree, board)?)),
+            Source::Table(..) => {
+                let (evaluation, _) = self.look_up(board)?;
+                // Taking the last of the best moves each time gives the same line as searching the tree.
+                let mut line = Vec::new();
+                let mut current = board;
+                while let Some(m) = self.look_up(current)?.1.last() {
+                    line.push(*m);
+                    current = current.apply(*m)?;
+                }
+                Ok((evaluation, line))
+            },
+        }
+    }
+
+    fn find(tree: &Tree<G>, board: G) -> Result<Node<'_, G>, String> {
+        tree.find_or_expand(board).ok_or(format!("Position is not reachable from the solver's root:\n{}", board.to_string()))
+    }
 
-        let active_player = match self.tree.get_root().get_active_player() {
+    fn look_up(&self, board: G) -> Result<(Evaluation, Vec<G::Move>), String> {
+        let entry = match &self.source {
+            Source::Tree(_) => None,
+            Source::Table(_, table) => table.look_up(board),
+        };
+        entry.ok_or(format!("Position is not in the table:\n{}", board.to_string()))
+    }
+
+    fn get_next_moves_and_evaluation_for_node(&self, root: &Node<G>) -> Result<(Vec<G::Move>, Evaluation), String> {
+
+        let active_player = match root.get_active_player() {
             Some(player) => player,
             None => return Err("The game is already over.".to_string()),
         };
 
-        let root = self.tree.get_root();
-        let mut next_moves = Vec::new();
-        let mut best_evaluation = Evaluation(
+        if let Some(Entry { lower, best_moves: Some(best_moves), .. }) = self.transpositions.borrow().get(root.get_board()) {
+            return Ok((best_moves, lower));
+        }
+
+        // A move is best when its position is at least as good as the root for the player to move, which a search
+        // with the window closed at the root's evaluation answers without finding the child's exact evaluation.
+        let evaluation = self.alpha_beta(root, LOWEST, HIGHEST);
+        let next_moves = root.get_legal_moves().into_iter().filter(|m| {
+            let child = root.get_child_by_move(*m).unwrap();
             match active_player {
-                Player::X => -2.,
-                Player::O => 2.,
+                Player::X => self.alpha_beta_child(&child, LOWEST, evaluation) >= evaluation,
+                Player::O => self.alpha_beta_child(&child, evaluation, HIGHEST) <= evaluation,
             }
-        );
+        }).collect::<Vec<G::Move>>();
+        self.transpositions.borrow_mut().store_best_moves(root.get_board(), evaluation, &next_moves);
+        Ok((next_moves, evaluation))
+    }
 
-        for m in root.get_legal_moves().iter() {
-            let child = root.get_child(m.get_row(), m.get_column()).unwrap();
-            let (evaluation, _line) = self.get_evaluation_and_line_for_node(child);
-            if ((evaluation > best_evaluation) && (active_player == Player::X)) ||
-                ((evaluation < best_evaluation) && (active_player == Player::O)) {
-                best_evaluation = evaluation;
-                next_moves.clear();
-                next_moves.push(*m);
-            } else if evaluation == best_evaluation {
-                next_moves.push(*m);
-            }
+    // The best moves again, but with the ones that give the opponent the most chances to go wrong first.
+    pub fn get_trap_scores(&self) -> Result<(Vec<TrapScore<G>>, Evaluation), String> {
+        get_trap_scores(self, self.get_board())
+    }
+
+    pub fn get_evaluation_and_line(&self) -> (Evaluation, Vec<G::Move>) {
+        self.get_evaluation_and_line_for(self.get_board()).expect("The solver's own position is always reachable.")
+    }
+
+    // An answer within the limits instead of a full solve, saying whether it's proven or only the best seen so far.
+    // It searches the position directly rather than the tree, so it starts straight away however large the game is.
+    pub fn search_iteratively(&self, limits: &SearchLimits) -> Result<SearchResult<G>, String> {
+        Deepening::new(limits, &*self.evaluator).search(self.get_board())
+    }
+
+    pub fn n_solved_positions(&self) -> usize {
+        self.transpositions.borrow().len()
+    }
+
+    pub fn n_nodes_searched(&self) -> usize {
+        self.nodes_searched.get()
+    }
+
+}
+
+impl<G> Solver<G> where G: Game + Send + Sync, G::Move: Send + Sync {
+
+    // Solves the root on `threads` threads up front, leaving the bounds it proved in the transposition table. The
+    // answers are the same as without it, since they are exact either way; only where they come from changes.
+    // Every move from the root is solved exactly, so the root's best moves need no more searching, but anything
+    // deeper, like the rest of the line, is still searched on this thread.
+    pub fn with_threads(self, threads: usize) -> Result<Self, String> {
+        if threads <= 1 {
+            return Ok(self);
+        }
+        if let Source::Tree(tree) = &self.source {
+            let search = ParallelSearch::new(&*self.evaluator);
+            search.evaluate(tree.get_root().get_board(), threads)?;
+            self.nodes_searched.set(self.nodes_searched.get() + search.n_nodes_searched());
+            self.transpositions.borrow_mut().merge(search.into_table());
+        }
+        Ok(self)
+    }
+
+}
+
+impl Solver<Board> {
+
+    pub fn from_table(board: Board, table: Table) -> Result<Self, String> {
+        if table.get(board).is_none() {
+            return Err(format!("Position is not in the table:\n{}", board.to_string()));
+        }
+        Solver::from_solved_table(board, Box::new(table))
+    }
+
+    pub fn from_tablebase(board: Board, tablebase: Tablebase) -> Result<Self, String> {
+        if tablebase.get(board).is_none() {
+            return Err(format!("Position is not in the tablebase:\n{}", board.to_string()));
         }
-        return Ok((next_moves, best_evaluation));
+        Solver::from_solved_table(board, Box::new(tablebase))
+    }
+
+    // Every legal move with its evaluation, rather than just the best ones.
+    pub fn analyze(&self) -> Result<Analysis, String> {
+        Analysis::for_board(self, self.get_board())
     }
 
-    pub fn get_evaluation_and_line(&self) -> (Evaluation, Vec<Move>) {
-        let root = self.tree.get_root();
-        self.get_evaluation_and_line_for_node(root)
+    // What the opponent does against `m`, and how much worse it is than the best moves.
+    pub fn refute(&self, m: Move) -> Result<Refutation, String> {
+        Refutation::for_move(self, self.get_board(), m)
     }
 
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
-pub struct Evaluation(f32);
+// The result of best play from X's point of view, with the number of plies until the game ends. X prefers
+// faster wins and slower losses, so a `Win` is better the sooner it comes and a `Loss` the later it comes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Evaluation {
+    Win(usize),
+    Draw,
+    Loss(usize),
+    // Not a result at all, but an evaluator's guess at a position the search stopped short of, positive when it
+    // favours X. Every guess ranks below any win and above any loss.
+    Heuristic(i32),
+}
 
 impl Evaluation {
 
-    pub fn new(evaluation: f32) -> Self {
-        Evaluation(evaluation)
+    pub fn get_score(&self) -> f32 {
+        match self {
+            Evaluation::Win(_) => 1.,
+            Evaluation::Draw | Evaluation::Heuristic(_) => 0.,
+            Evaluation::Loss(_) => -1.,
+        }
     }
 
-    pub fn get_evaluation(&self) -> f32 {
-        self.0
+    pub fn get_plies(&self) -> Option<usize> {
+        match self {
+            Evaluation::Win(plies) | Evaluation::Loss(plies) => Some(*plies),
+            Evaluation::Draw | Evaluation::Heuristic(_) => None,
+        }
+    }
+
+    // The same result as seen from the position one ply before.
+    pub fn next_ply(self) -> Evaluation {
+        match self {
+            Evaluation::Win(plies) => Evaluation::Win(plies + 1),
+            Evaluation::Loss(plies) => Evaluation::Loss(plies + 1),
+            Evaluation::Draw | Evaluation::Heuristic(_) => self,
+        }
+    }
+
+    // The inverse of `next_ply`, except that a finished game has nothing after it and stays as it is.
+    pub fn previous_ply(self) -> Evaluation {
+        match self {
+            Evaluation::Win(plies) => Evaluation::Win(plies.saturating_sub(1)),
+            Evaluation::Loss(plies) => Evaluation::Loss(plies.saturating_sub(1)),
+            Evaluation::Draw | Evaluation::Heuristic(_) => self,
+        }
+    }
+
+    // An even guess ranks just above a proven draw, only so that no two evaluations rank the same.
+    fn rank(&self) -> (i8, i64, i8) {
+        match self {
+            Evaluation::Win(plies) => (1, -(*plies as i64), 0),
+            Evaluation::Draw => (0, 0, 0),
+            Evaluation::Heuristic(score) => (0, *score as i64, 1),
+            Evaluation::Loss(plies) => (-1, *plies as i64, 0),
+        }
     }
 
     pub fn to_string(&self) -> String {
-        if self.0 > 0.9999 {
-            return "X is Winning".to_string();
-        } else if self.0 < -0.9999 {
-            return "O is Winning".to_string();
-        } else if self.0.abs() < 0.0001 {
-            return "Drawn".to_string();
-        } else {
-            return "Ambiguous".to_string();
+        match self {
+            Evaluation::Win(0) => "X has won".to_string(),
+            Evaluation::Loss(0) => "O has won".to_string(),
+            Evaluation::Win(plies) => format!("X wins in {}", plies),
+            Evaluation::Loss(plies) => format!("O wins in {}", plies),
+            Evaluation::Draw => "Drawn".to_string(),
+            Evaluation::Heuristic(score) => format!("Guess {:+}", score),
         }
     }
 }
 
+impl Ord for Evaluation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Evaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 
 #[cfg(test)]
 mod test_solver {
     use super::*;
+    use crate::board::board::Move;
 
     #[test]
     fn test_solver_gets_evaluation() {
@@ -150,7 +450,7 @@ mod test_solver {
                     XOX",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(1.)
+            Evaluation::Win(0)
         );
         
         assert_eq!(
@@ -161,7 +461,7 @@ mod test_solver {
                     XOX",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(1.)
+            Evaluation::Win(1)
         );
 
         assert_eq!(
@@ -172,7 +472,7 @@ mod test_solver {
                     XXO",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(0.)
+            Evaluation::Draw
         );
 
         assert_eq!(
@@ -183,7 +483,7 @@ mod test_solver {
                     XXO",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(-1.)
+            Evaluation::Loss(1)
         );
 
         
@@ -194,7 +494,7 @@ mod test_solver {
                     O__
                     XXO",
                 ).unwrap()
-            ).get_evaluation() < Evaluation(0.0001)
+            ).get_evaluation() <= Evaluation::Draw
         );
     }
 
@@ -210,7 +510,7 @@ mod test_solver {
         let solver = Solver::from_board(board);
 
         let (evaluation, line) = solver.get_evaluation_and_line();
-        assert_eq!(evaluation, Evaluation(-1.));
+        assert_eq!(evaluation, Evaluation::Loss(2));
         assert_eq!(line, vec![Move { row: 2, col: 2 }, Move { row: 2, col: 1} ]);
 
         let board = Board::from_position(
@@ -222,11 +522,11 @@ mod test_solver {
         let solver = Solver::from_board(board);
 
         let (evaluation, line) = solver.get_evaluation_and_line();
-        assert_eq!(evaluation, Evaluation(-1.));
+        assert_eq!(evaluation, Evaluation::Loss(1));
         assert_eq!(line, vec![Move { row: 2, col: 1 }]);
 
         let (best_moves, _) = solver.get_next_moves_and_evaluation().unwrap();
-        assert_eq!(best_moves, vec![Move { row: 2, col: 0 }, Move { row: 2, col: 1 }]);
+        assert_eq!(best_moves, vec![Move { row: 2, col: 1 }]);
 
         let board = Board::from_position(
             "XOO
@@ -236,11 +536,112 @@ mod test_solver {
         assert_eq!(board.get_active_player(), Some(Player::X));
         let solver = Solver::from_board(board);
         let (evaluation, line) = solver.get_evaluation_and_line();
-        assert_eq!(evaluation, Evaluation(1.));
+        assert_eq!(evaluation, Evaluation::Win(1));
         assert_eq!(line, vec![Move::new(2, 2)]);
 
         let (best_moves, _) = solver.get_next_moves_and_evaluation().unwrap();
-        assert_eq!(best_moves, vec![Move { row: 1, col: 0 }, Move { row: 1, col: 2 }, Move { row: 2, col: 0 }, Move { row: 2, col: 2 }]);
+        assert_eq!(best_moves, vec![Move { row: 2, col: 2 }]);
 
     }
+
+    #[test]
+    fn test_solver_prunes_the_empty_board() {
+        let solver = Solver::from_board(Board::empty());
+        assert_eq!(solver.get_evaluation(), Evaluation::Draw);
+        let searched = solver.n_nodes_searched();
+
+        // Plain minimax visits every node of the game tree, or every position once when it memoizes.
+        let tree = Tree::from_board(Board::empty());
+        assert_eq!(tree.get_root().get_subtree_size(), 549946);
+        assert_eq!(tree.n_positions(), 5478);
+        assert!(searched < tree.n_positions());
+        assert!(solver.n_solved_positions() < tree.n_positions());
+
+        let (best_moves, evaluation) = solver.get_next_moves_and_evaluation().unwrap();
+        assert_eq!(evaluation, Evaluation::Draw);
+        assert_eq!(best_moves.len(), 9);
+        assert!(solver.n_nodes_searched() < tree.n_positions());
+    }
+
+    #[test]
+    fn test_solver_answers_repeated_queries_from_its_table() {
+        let solver = Solver::from_board(Board::empty());
+        let (evaluation, line) = solver.get_evaluation_and_line();
+        let (best_moves, _) = solver.get_next_moves_and_evaluation().unwrap();
+        let searched = solver.n_nodes_searched();
+
+        assert_eq!(solver.get_evaluation_and_line(), (evaluation, line.clone()));
+        assert_eq!(solver.get_next_moves_and_evaluation().unwrap(), (best_moves, evaluation));
+        let after_first_move = Board::empty().with_move_made(Player::X, line[0]).unwrap();
+        assert_eq!(solver.get_evaluation_and_line_for(after_first_move).unwrap(), (evaluation, line[1..].to_vec()));
+        assert_eq!(solver.n_nodes_searched(), searched);
+    }
+
+    #[test]
+    fn test_solver_shares_symmetric_positions() {
+        let solver = Solver::from_board(Board::empty());
+        let symmetric_solver = Solver::from_board(Board::empty()).sharing_symmetries();
+        for board in Tree::from_board(Board::empty()).get_positions() {
+            assert_eq!(
+                symmetric_solver.get_next_moves_and_evaluation_for(board),
+                solver.get_next_moves_and_evaluation_for(board)
+            );
+        }
+        assert_eq!(solver.n_solved_positions(), 5478);
+        assert!(symmetric_solver.n_solved_positions() <= 765);
+        assert!(symmetric_solver.n_nodes_searched() < solver.n_nodes_searched());
+    }
+
+    fn minimax(board: Board) -> Evaluation {
+        match board.get_active_player() {
+            None => Solver::<Board>::get_evaluation_for_outcome(board.get_outcome()).unwrap(),
+            Some(player) => {
+                let evaluations = board.get_legal_moves().into_iter()
+                    .map(|m| minimax(board.with_move_made(player, m).unwrap()).next_ply());
+                match player {
+                    Player::X => evaluations.max().unwrap(),
+                    Player::O => evaluations.min().unwrap(),
+                }
+            },
+        }
+    }
+
+    #[test]
+    fn test_solver_agrees_with_minimax() {
+        let board = Board::from_position(
+            "X__
+            _O_
+            ___"
+        ).unwrap();
+        let solver = Solver::from_board(board);
+        for position in Tree::from_board(board).get_positions() {
+            let player = match position.get_active_player() {
+                Some(player) => player,
+                None => continue,
+            };
+            let evaluations: Vec<(Move, Evaluation)> = position.get_legal_moves().into_iter()
+                .map(|m| (m, minimax(position.with_move_made(player, m).unwrap()).next_ply()))
+                .collect();
+            let best = minimax(position);
+            let best_moves: Vec<Move> = evaluations.iter().filter(|(_, e)| *e == best).map(|(m, _)| *m).collect();
+            assert_eq!(solver.get_next_moves_and_evaluation_for(position).unwrap(), (best_moves, best));
+        }
+    }
+
+    #[test]
+    fn test_solver_plays_a_game_without_resolving() {
+        let mut solver = Solver::from_board(Board::empty());
+        assert_eq!(solver.get_evaluation(), Evaluation::Draw);
+        let mut n_solved = solver.n_solved_positions();
+
+        while let Ok(best_moves) = solver.get_next_moves() {
+            solver.play(best_moves[0]).unwrap();
+            assert_eq!(solver.get_evaluation(), Evaluation::Draw);
+            assert!(solver.n_solved_positions() < n_solved);
+            n_solved = solver.n_solved_positions();
+        }
+        assert_eq!(solver.get_board().get_outcome(), Outcome::Draw);
+        assert_eq!(solver.n_solved_positions(), 1);
+        assert!(solver.play(Move::new(0, 0)).is_err());
+    }
 }
\ No newline at end of file
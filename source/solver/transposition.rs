@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use crate::board::board::Board;
+use crate::game::game::{Game, GameSymmetry};
+use crate::solver::solver::Evaluation;
+
+// What the solver has proven about a position: bounds on its evaluation and, once known, its best moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry<G: Game = Board> {
+    pub lower: Evaluation,
+    pub upper: Evaluation,
+    pub best_moves: Option<Vec<G::Move>>,
+}
+
+// Entries are keyed by board, or by canonical board so that all 8 symmetric positions share one entry. Best
+// moves are stored as seen from the key and mapped back onto the board that asked for them.
+pub struct TranspositionTable<G: Game = Board> {
+    entries: HashMap<G, Entry<G>>,
+    canonical: bool,
+}
+
+impl<G: Game> TranspositionTable<G> {
+
+    pub fn new() -> Self {
+        TranspositionTable { entries: HashMap::new(), canonical: false }
+    }
+
+    pub fn canonical() -> Self {
+        TranspositionTable { entries: HashMap::new(), canonical: true }
+    }
+
+    fn key(&self, board: G) -> (G, G::Symmetry) {
+        if self.canonical {
+            board.canonical()
+        } else {
+            (board, G::Symmetry::identity())
+        }
+    }
+
+    pub fn get(&self, board: G) -> Option<Entry<G>> {
+        let (key, symmetry) = self.key(board);
+        let mut entry = self.entries.get(&key)?.clone();
+        if let Some(best_moves) = &mut entry.best_moves {
+            *best_moves = TranspositionTable::<G>::transform_moves(best_moves, &symmetry.inverse());
+        }
+        Some(entry)
+    }
+
+    pub fn store_bounds(&mut self, board: G, lower: Evaluation, upper: Evaluation) {
+        let (key, _) = self.key(board);
+        match self.entries.get_mut(&key) {
+            Some(entry) => {
+                entry.lower = lower;
+                entry.upper = upper;
+            },
+            None => {
+                self.entries.insert(key, Entry { lower, upper, best_moves: None });
+            },
+        }
+    }
+
+    // Best moves are only meaningful once the evaluation is exact, so they are stored together with it.
+    pub fn store_best_moves(&mut self, board: G, evaluation: Evaluation, best_moves: &[G::Move]) {
+        let (key, symmetry) = self.key(board);
+        let best_moves = TranspositionTable::<G>::transform_moves(best_moves, &symmetry);
+        self.entries.insert(key, Entry { lower: evaluation, upper: evaluation, best_moves: Some(best_moves) });
+    }
+
+    // Keeps the entries of positions for which `keep` holds for the board or, in a canonical table, for any of
+    // its symmetric images.
+    pub fn retain(&mut self, mut keep: impl FnMut(G) -> bool) {
+        let canonical = self.canonical;
+        self.entries.retain(|key, _| {
+            if canonical {
+                G::Symmetry::all().iter().any(|symmetry| keep(symmetry.transform(key)))
+            } else {
+                keep(*key)
+            }
+        });
+    }
+
+    // Takes in everything another table has proven. Bounds on the same position are combined, keeping the tighter
+    // of each.
+    pub fn merge(&mut self, other: TranspositionTable<G>) {
+        for (position, entry) in other.entries {
+            match entry.best_moves {
+                Some(best_moves) => self.store_best_moves(position, entry.lower, &best_moves),
+                None => {
+                    let (lower, upper) = match self.get(position) {
+                        Some(existing) => (existing.lower.max(entry.lower), existing.upper.min(entry.upper)),
+                        None => (entry.lower, entry.upper),
+                    };
+                    self.store_bounds(position, lower, upper);
+                },
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Moves always come out in order, which is row-major for `Board`, like `Board::get_legal_moves`.
+    fn transform_moves(moves: &[G::Move], symmetry: &G::Symmetry) -> Vec<G::Move> {
+        let mut transformed: Vec<G::Move> = moves.iter().map(|m| symmetry.transform_move(*m)).collect();
+        transformed.sort();
+        transformed
+    }
+
+}
+
+impl<G: Game> Default for TranspositionTable<G> {
+    fn default() -> Self {
+        TranspositionTable::new()
+    }
+}
+
+#[cfg(test)]
+mod test_transposition {
+    use super::*;
+    use crate::board::board::Move;
+
+    #[test]
+    fn test_transposition_table_shares_symmetric_positions() {
+        let board = Board::from_position(
+            "X__
+            ___
+            _O_"
+        ).unwrap();
+        let mirrored = Board::from_position(
+            "__X
+            ___
+            _O_"
+        ).unwrap();
+
+        let mut table = TranspositionTable::canonical();
+        table.store_best_moves(board, Evaluation::Win(3), &[Move::new(0, 2), Move::new(1, 0)]);
+        assert_eq!(table.len(), 1);
+
+        let entry = table.get(mirrored).unwrap();
+        assert_eq!(entry.lower, entry.upper);
+        assert_eq!(entry.best_moves, Some(vec![Move::new(0, 0), Move::new(1, 2)]));
+        assert_eq!(table.get(board).unwrap().best_moves, Some(vec![Move::new(0, 2), Move::new(1, 0)]));
+
+        let mut table = TranspositionTable::new();
+        table.store_bounds(board, Evaluation::Draw, Evaluation::Win(3));
+        assert!(table.get(mirrored).is_none());
+        assert_ne!(table.get(board).unwrap().lower, table.get(board).unwrap().upper);
+
+        table.retain(|b| b != board);
+        assert_eq!(table.len(), 0);
+    }
+
+}
\ No newline at end of file
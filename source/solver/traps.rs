@@ -0,0 +1,96 @@
+use std::cmp::Reverse;
+
+use crate::game::game::{Game, Player};
+use crate::solver::solver::{Evaluation, Solver};
+
+// How testing a best move is: how many of the opponent's replies throw away the result they'd get with best play.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrapScore<G: Game> {
+    pub m: G::Move,
+    pub losing_replies: usize,
+    pub replies: usize,
+}
+
+impl<G: Game> TrapScore<G> {
+
+    // The chance that an opponent picking a reply at random goes wrong. One that plays its best move except for a
+    // random one a fraction `epsilon` of the time goes wrong `epsilon` times this often.
+    pub fn get_error_probability(&self) -> f64 {
+        match self.replies {
+            0 => 0.,
+            replies => self.losing_replies as f64 / replies as f64,
+        }
+    }
+
+    fn rank(&self) -> (u64, usize) {
+        ((self.get_error_probability() * 1e9) as u64, self.losing_replies)
+    }
+
+}
+
+// The trap score of each of the best moves, most testing first and otherwise in the order of their moves. A reply
+// only counts as going wrong when it changes who wins, not when it just loses more slowly.
+pub fn get_trap_scores<G: Game>(solver: &Solver<G>, board: G) -> Result<(Vec<TrapScore<G>>, Evaluation), String> {
+    let (best_moves, evaluation) = solver.get_next_moves_and_evaluation_for(board)?;
+    let mut scores = Vec::new();
+    for m in best_moves {
+        let child = board.apply(m)?;
+        let opponent = match child.get_active_player() {
+            Some(opponent) => opponent,
+            None => {
+                scores.push(TrapScore { m, losing_replies: 0, replies: 0 });
+                continue;
+            },
+        };
+        let replies = child.get_legal_moves();
+        let mut losing_replies = 0;
+        for reply in replies.iter() {
+            let (reply_evaluation, _) = solver.get_evaluation_and_line_for(child.apply(*reply)?)?;
+            let goes_wrong = match opponent {
+                Player::X => reply_evaluation.get_score() < evaluation.get_score(),
+                Player::O => reply_evaluation.get_score() > evaluation.get_score(),
+            };
+            if goes_wrong {
+                losing_replies += 1;
+            }
+        }
+        scores.push(TrapScore { m, losing_replies, replies: replies.len() });
+    }
+    scores.sort_by_key(|score| Reverse(score.rank()));
+    Ok((scores, evaluation))
+}
+
+#[cfg(test)]
+mod test_traps {
+    use super::*;
+    use crate::board::board::{Board, Move};
+
+    #[test]
+    fn test_corners_set_the_most_traps() {
+        let solver = Solver::from_board(Board::empty());
+        let (scores, evaluation) = solver.get_trap_scores().unwrap();
+        assert_eq!(evaluation, Evaluation::Draw);
+        assert_eq!(scores.len(), 9);
+        // Against a corner only the centre holds the draw, while against the centre or an edge half the replies do.
+        assert_eq!(scores[0], TrapScore { m: Move::new(0, 0), losing_replies: 7, replies: 8 });
+        assert!(scores[..4].iter().all(|score| score.losing_replies == 7));
+        assert!(scores[4..].iter().all(|score| score.losing_replies == 4));
+        assert_eq!(scores[4].m, Move::new(0, 1));
+        assert!(scores.windows(2).all(|pair| pair[0].get_error_probability() >= pair[1].get_error_probability()));
+    }
+
+    #[test]
+    fn test_trap_scores_of_winning_moves() {
+        let board = Board::from_position(
+            "XX_
+            OO_
+            ___"
+        ).unwrap();
+        let (scores, evaluation) = Solver::from_board(board).get_trap_scores().unwrap();
+        assert_eq!(evaluation, Evaluation::Win(1));
+        assert_eq!(scores, vec![TrapScore { m: Move::new(0, 2), losing_replies: 0, replies: 0 }]);
+        assert_eq!(scores[0].get_error_probability(), 0.);
+
+        assert!(Solver::from_board(Board::from_position("XXXOO____").unwrap()).get_trap_scores().is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,2 @@
+pub mod table;
+pub mod tablebase;
\ No newline at end of file
@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::ops::Deref;
+
+use memmap2::Mmap;
+
+use crate::board::board::{Board, Move};
+use crate::solver::solver::{Evaluation, SolvedTable, Solver};
+use crate::tree::tree::Tree;
+
+// File layout, all little-endian:
+//
+//   header (16 bytes)
+//     0..4    magic, b"TTTS"
+//     4..6    format version (u16)
+//     6       rules variant (u8), 0 for standard tic-tac-toe
+//     7       rows (u8)
+//     8       columns (u8)
+//     9..12   reserved, zero
+//     12..16  number of entries (u32)
+//   entries (8 bytes each), sorted by key
+//     0..4    board key, as given by `Board::key` (u32)
+//     4       evaluation, 1 for an X win, 0 for a draw, -1 for an O win (i8)
+//     5       distance: moves left in the game with best play (u8)
+//     6..8    best moves, bit `row * 3 + col` set for each (u16); only the fastest wins and slowest losses count
+//
+// Version 1 counted every winning move as best, and its distances followed whichever win the solver found.
+pub const MAGIC: &[u8; 4] = b"TTTS";
+pub const FORMAT_VERSION: u16 = 2;
+pub const STANDARD_RULES: u8 = 0;
+const HEADER_SIZE: usize = 16;
+const ENTRY_SIZE: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableEntry {
+    pub key: u32,
+    pub evaluation: i8,
+    pub distance: u8,
+    pub best_moves: u16,
+}
+
+impl TableEntry {
+
+    pub fn new(board: Board, evaluation: Evaluation, distance: usize, best_moves: &[Move]) -> Self {
+        TableEntry {
+            key: board.key(),
+            evaluation: evaluation.get_score() as i8,
+            distance: distance as u8,
+            best_moves: best_moves.iter().fold(0, |mask, m| mask | (1 << (m.get_row() * 3 + m.get_column()))),
+        }
+    }
+
+    pub fn get_evaluation(&self) -> Evaluation {
+        match self.evaluation {
+            1 => Evaluation::Win(self.distance as usize),
+            -1 => Evaluation::Loss(self.distance as usize),
+            _ => Evaluation::Draw,
+        }
+    }
+
+    pub fn get_best_moves(&self) -> Vec<Move> {
+        (0..9)
+            .filter(|square| self.best_moves & (1 << square) != 0)
+            .map(|square| Move::new(square / 3, square % 3))
+            .collect()
+    }
+
+    fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0; ENTRY_SIZE];
+        bytes[0..4].copy_from_slice(&self.key.to_le_bytes());
+        bytes[4] = self.evaluation as u8;
+        bytes[5] = self.distance;
+        bytes[6..8].copy_from_slice(&self.best_moves.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        TableEntry {
+            key: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            evaluation: bytes[4] as i8,
+            distance: bytes[5],
+            best_moves: u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
+        }
+    }
+
+}
+
+enum TableBytes {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Deref for TableBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            TableBytes::Owned(bytes) => bytes,
+            TableBytes::Mapped(map) => map,
+        }
+    }
+}
+
+// A solved table, queried straight from the file's bytes.
+pub struct Table {
+    bytes: TableBytes,
+    n_entries: usize,
+}
+
+impl Table {
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut bytes = Vec::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_end(&mut bytes))
+            .map_err(|error| format!("Couldn't read table {}: {}", path, error))?;
+        Table::from_vec(bytes)
+    }
+
+    pub fn map(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|error| format!("Couldn't open table {}: {}", path, error))?;
+        // The file is only ever read, but the map is still invalid if something else changes it while it's open.
+        let map = unsafe { Mmap::map(&file) }.map_err(|error| format!("Couldn't map table {}: {}", path, error))?;
+        Table::from_bytes(TableBytes::Mapped(map))
+    }
+
+    pub fn from_vec(bytes: Vec<u8>) -> Result<Self, String> {
+        Table::from_bytes(TableBytes::Owned(bytes))
+    }
+
+    fn from_bytes(bytes: TableBytes) -> Result<Self, String> {
+        if bytes.len() < HEADER_SIZE || &bytes[0..4] != MAGIC {
+            return Err("Not a solved table".to_string());
+        }
+        let version = u16::from_le_bytes(bytes[4..6].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            return Err(format!("Unsupported table version {}, expected {}", version, FORMAT_VERSION));
+        }
+        if (bytes[6], bytes[7], bytes[8]) != (STANDARD_RULES, 3, 3) {
+            return Err(format!("Unsupported rules: variant {} on a {}x{} board", bytes[6], bytes[7], bytes[8]));
+        }
+        let n_entries = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        if bytes.len() != HEADER_SIZE + n_entries * ENTRY_SIZE {
+            return Err(format!("Table should hold {} entries but is {} bytes long", n_entries, bytes.len()));
+        }
+        Ok(Table { bytes, n_entries })
+    }
+
+    fn entry(&self, i: usize) -> TableEntry {
+        let start = HEADER_SIZE + i * ENTRY_SIZE;
+        TableEntry::from_bytes(&self.bytes[start..start + ENTRY_SIZE])
+    }
+
+    pub fn get(&self, board: Board) -> Option<TableEntry> {
+        let key = board.key();
+        let (mut low, mut high) = (0, self.n_entries);
+        while low < high {
+            let middle = (low + high) / 2;
+            let entry = self.entry(middle);
+            if entry.key == key {
+                return Some(entry);
+            } else if entry.key < key {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        None
+    }
+
+}
+
+impl SolvedTable<Board> for Table {
+    fn look_up(&self, board: Board) -> Option<(Evaluation, Vec<Move>)> {
+        self.get(board).map(|entry| (entry.get_evaluation(), entry.get_best_moves()))
+    }
+}
+
+// Solves every position reachable from the empty board.
+pub fn build_table() -> Vec<TableEntry> {
+    let solver = Solver::from_board(Board::empty());
+    let mut entries = Tree::from_board(Board::empty()).get_positions().iter()
+        .map(|board| {
+            let (evaluation, line) = solver.get_evaluation_and_line_for(*board).expect("Every position is reachable from the empty board.");
+            let best_moves = match solver.get_next_moves_and_evaluation_for(*board) {
+                Ok((best_moves, _)) => best_moves,
+                Err(_) => Vec::new(),
+            };
+            TableEntry::new(*board, evaluation, line.len(), &best_moves)
+        })
+        .collect::<Vec<TableEntry>>();
+    entries.sort_by_key(|entry| entry.key);
+    entries
+}
+
+pub fn write_table(entries: &[TableEntry], out: &mut impl Write) -> io::Result<()> {
+    let mut header = [0; HEADER_SIZE];
+    header[0..4].copy_from_slice(MAGIC);
+    header[4..6].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header[6] = STANDARD_RULES;
+    header[7] = 3;
+    header[8] = 3;
+    header[12..16].copy_from_slice(&(entries.len() as u32).to_le_bytes());
+    out.write_all(&header)?;
+    for entry in entries.iter() {
+        out.write_all(&entry.to_bytes())?;
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod test_table {
+    use super::*;
+
+    fn built_table() -> Table {
+        let mut bytes = Vec::new();
+        write_table(&build_table(), &mut bytes).unwrap();
+        Table::from_vec(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_table_round_trips() {
+        let table = built_table();
+        assert_eq!(table.n_entries, 5478);
+
+        let entry = table.get(Board::from_position(
+            "XO_
+            _X_
+            __O",
+        ).unwrap()).unwrap();
+        assert_eq!(entry.get_evaluation(), Evaluation::Win(3));
+        assert_eq!(entry.get_best_moves(), vec![Move::new(1, 0), Move::new(2, 0)]);
+
+        let finished = table.get(Board::from_position(
+            "XOX
+            OXO
+            XOX",
+        ).unwrap()).unwrap();
+        assert_eq!((finished.evaluation, finished.distance, finished.best_moves), (1, 0, 0));
+
+        assert_eq!(table.get(Board::from_position("XXXXXXXXX").unwrap()), None);
+    }
+
+    #[test]
+    fn test_table_rejects_bad_files() {
+        assert!(Table::from_vec(b"nonsense".to_vec()).is_err());
+
+        let mut bytes = Vec::new();
+        write_table(&build_table()[..10], &mut bytes).unwrap();
+        assert!(Table::from_vec(bytes.clone()).is_ok());
+        assert!(Table::from_vec(bytes[..bytes.len() - 1].to_vec()).is_err());
+        bytes[4] = 1;
+        assert!(Table::from_vec(bytes).is_err());
+    }
+
+    #[test]
+    fn test_solver_answers_from_table() {
+        for position in ["_________", "XO_XOX___", "XO__X___O", "XOXO__XOX", "XO_O__XXO"] {
+            let board = Board::from_position(position).unwrap();
+            let tree_solver = Solver::from_board(board);
+            let table_solver = Solver::from_table(board, built_table()).unwrap();
+            assert_eq!(table_solver.get_evaluation_and_line(), tree_solver.get_evaluation_and_line());
+            assert_eq!(table_solver.get_next_moves_and_evaluation(), tree_solver.get_next_moves_and_evaluation());
+        }
+
+        let mut solver = Solver::from_table(Board::empty(), built_table()).unwrap();
+        solver.play(Move::new(1, 1)).unwrap();
+        assert_eq!(solver.get_board(), Board::from_position("____X____").unwrap());
+        assert!(solver.play(Move::new(1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_table_maps_files() {
+        let path = std::env::temp_dir().join(format!("tic-tac-toe-table-{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        write_table(&build_table(), &mut File::create(path).unwrap()).unwrap();
+        let mapped = Table::map(path).unwrap();
+        let loaded = Table::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let board = Board::from_position("XO_XOX___").unwrap();
+        assert_eq!(mapped.get(board), loaded.get(board));
+        assert_eq!(mapped.get(board), built_table().get(board));
+    }
+}
\ No newline at end of file
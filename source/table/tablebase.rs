@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::board::board::{Board, Move};
+use crate::game::game::{Outcome, Player};
+use crate::solver::solver::{Evaluation, SolvedTable, Solver};
+use crate::table::table::TableEntry;
+
+// Every position reachable from the empty board, solved by working backwards from the finished games.
+pub struct Tablebase {
+    entries: HashMap<Board, TableEntry>,
+}
+
+impl Tablebase {
+
+    pub fn generate() -> Self {
+        let (positions, parents) = Tablebase::get_positions_and_parents();
+
+        // Finished games are known straight away. Drawn ones are left out of the queue: only wins and losses are
+        // pushed back to the positions before them, and whatever is never reached that way is a draw.
+        let mut evaluations: HashMap<Board, Evaluation> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for board in positions.iter() {
+            match board.get_outcome() {
+                Outcome::Victory(Player::X) => {
+                    evaluations.insert(*board, Evaluation::Win(0));
+                    queue.push_back(*board);
+                },
+                Outcome::Victory(Player::O) => {
+                    evaluations.insert(*board, Evaluation::Loss(0));
+                    queue.push_back(*board);
+                },
+                Outcome::Draw | Outcome::Ambiguous => {
+                    evaluations.insert(*board, Evaluation::Draw);
+                },
+                Outcome::InProgress => (),
+            }
+        }
+
+        // The number of moves from each position that haven't been shown to lose for the player making them.
+        let mut unresolved: HashMap<Board, usize> = positions.iter()
+            .map(|board| (*board, board.get_legal_moves().len()))
+            .collect();
+
+        // Positions come off the queue in order of distance, so the first winning move found from a position is
+        // the fastest, and the last losing move found is the slowest.
+        while let Some(board) = queue.pop_front() {
+            let evaluation = evaluations[&board];
+            let winner = match evaluation {
+                Evaluation::Win(_) => Player::X,
+                Evaluation::Loss(_) => Player::O,
+                Evaluation::Draw | Evaluation::Heuristic(_) => panic!("Only wins and losses are queued."),
+            };
+            for parent in parents.get(&board).into_iter().flatten() {
+                if evaluations.contains_key(parent) {
+                    continue;
+                }
+                let remaining = unresolved.get_mut(parent).expect("Every parent is a reachable position.");
+                *remaining -= 1;
+                if parent.get_active_player() == Some(winner) || *remaining == 0 {
+                    evaluations.insert(*parent, evaluation.next_ply());
+                    queue.push_back(*parent);
+                }
+            }
+        }
+
+        let entries = positions.iter()
+            .map(|board| {
+                let evaluation = *evaluations.get(board).unwrap_or(&Evaluation::Draw);
+                let best_moves = match board.get_active_player() {
+                    Some(player) => board.get_legal_moves().into_iter()
+                        .filter(|m| {
+                            let child = board.with_move_made(player, *m).unwrap();
+                            evaluations.get(&child).unwrap_or(&Evaluation::Draw).next_ply() == evaluation
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                };
+                // A drawn game only ends once the board is full.
+                let distance = evaluation.get_plies().unwrap_or(board.get_legal_moves().len());
+                (*board, TableEntry::new(*board, evaluation, distance, &best_moves))
+            })
+            .collect();
+        Tablebase { entries }
+    }
+
+    // All reachable positions in the order they are first reached, along with the positions one move before each.
+    fn get_positions_and_parents() -> (Vec<Board>, HashMap<Board, Vec<Board>>) {
+        let mut positions = vec![Board::empty()];
+        let mut seen = HashSet::from([Board::empty()]);
+        let mut parents: HashMap<Board, Vec<Board>> = HashMap::new();
+        let mut i = 0;
+        while i < positions.len() {
+            let board = positions[i];
+            i += 1;
+            let player = match board.get_active_player() {
+                Some(player) => player,
+                None => continue,
+            };
+            for m in board.get_legal_moves() {
+                let child = board.with_move_made(player, m).unwrap();
+                parents.entry(child).or_default().push(board);
+                if seen.insert(child) {
+                    positions.push(child);
+                }
+            }
+        }
+        (positions, parents)
+    }
+
+    pub fn get(&self, board: Board) -> Option<TableEntry> {
+        self.entries.get(&board).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Sorted by key, ready for `write_table`.
+    pub fn get_entries(&self) -> Vec<TableEntry> {
+        let mut entries: Vec<TableEntry> = self.entries.values().copied().collect();
+        entries.sort_by_key(|entry| entry.key);
+        entries
+    }
+
+    // Compares every position against a forward solver built on the empty board.
+    pub fn check(&self, solver: &Solver) -> Result<(), String> {
+        for (board, entry) in self.entries.iter() {
+            let (evaluation, _) = solver.get_evaluation_and_line_for(*board)?;
+            let best_moves = match solver.get_next_moves_and_evaluation_for(*board) {
+                Ok((best_moves, _)) => best_moves,
+                Err(_) => Vec::new(),
+            };
+            if entry.get_evaluation() != evaluation || entry.get_best_moves() != best_moves {
+                return Err(format!(
+                    "Tablebase disagrees with the solver on\n{}\nTablebase: {} {:?}\nSolver: {} {:?}",
+                    board.to_string(),
+                    entry.get_evaluation().to_string(),
+                    entry.get_best_moves(),
+                    evaluation.to_string(),
+                    best_moves,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+}
+
+impl SolvedTable<Board> for Tablebase {
+    fn look_up(&self, board: Board) -> Option<(Evaluation, Vec<Move>)> {
+        self.get(board).map(|entry| (entry.get_evaluation(), entry.get_best_moves()))
+    }
+}
+
+#[cfg(test)]
+mod test_tablebase {
+    use super::*;
+    use crate::table::table::build_table;
+
+    #[test]
+    fn test_tablebase_agrees_with_forward_solver() {
+        let tablebase = Tablebase::generate();
+        assert_eq!(tablebase.len(), 5478);
+        assert_eq!(tablebase.check(&Solver::from_board(Board::empty())), Ok(()));
+        assert_eq!(tablebase.get_entries(), build_table());
+
+        let entry = tablebase.get(Board::from_position("XO__X___O").unwrap()).unwrap();
+        assert_eq!(entry.get_evaluation(), Evaluation::Win(3));
+        assert_eq!(entry.get_best_moves(), vec![Move::new(1, 0), Move::new(2, 0)]);
+        assert_eq!(tablebase.get(Board::from_position("XXXXXXXXX").unwrap()), None);
+    }
+
+    #[test]
+    fn test_solver_answers_from_tablebase() {
+        let board = Board::from_position("XO_XOX___").unwrap();
+        let tree_solver = Solver::from_board(board);
+        let mut solver = Solver::from_tablebase(board, Tablebase::generate()).unwrap();
+        assert_eq!(solver.get_evaluation_and_line(), tree_solver.get_evaluation_and_line());
+        assert_eq!(solver.get_next_moves_and_evaluation(), tree_solver.get_next_moves_and_evaluation());
+
+        solver.play(Move::new(2, 1)).unwrap();
+        assert_eq!(solver.get_evaluation(), Evaluation::Loss(0));
+        assert!(solver.get_next_moves().is_err());
+    }
+}
\ No newline at end of file
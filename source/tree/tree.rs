@@ -1,97 +1,584 @@
-use crate::board::board::{Board, Move, Outcome, Player};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::board::board::{Board, Move};
+use crate::game::game::{Game, GameSymmetry, Outcome, Player};
+
+// Positions are stored once each and shared between every parent that reaches them, so the "tree" is really a DAG.
+// Children are only generated the first time they're asked for, which is what lets a lazy tree stay small.
+pub struct Tree<G: Game = Board> {
+    nodes: RefCell<Vec<Option<NodeData<G>>>>,
+    // Bumped every time a slot is released, so that handles to a dropped node can't pick up whatever replaces it.
+    generations: RefCell<Vec<u32>>,
+    index: RefCell<HashMap<G, usize>>,
+    free: RefCell<Vec<usize>>,
+    root: usize,
+    // Maps the root's stored board onto the actual root position, which only differs once the root has been advanced into a shared node.
+    root_symmetry: G::Symmetry,
+    // How far the current root is below the position the tree was built from.
+    root_depth: usize,
+    up_to_symmetry: bool,
+    max_depth: Option<usize>,
+}
 
-pub struct Tree {
-    root: Node,
+// The move (relative to the parent's stored board), the child's index, and the symmetry that maps the child's stored board onto the board actually reached.
+type ChildEdge<G> = (<G as Game>::Move, usize, <G as Game>::Symmetry);
+// The same edge seen from the child: the parent's index, then the move and symmetry as in `ChildEdge`.
+type ParentEdge<G> = (usize, <G as Game>::Move, <G as Game>::Symmetry);
+
+struct NodeData<G: Game> {
+    board: G,
+    // Number of moves between the position the tree was built from and this one. `Game` requires this to be the same along every path.
+    depth: usize,
+    // The first parent this position was reached from, in the same form as that parent's entry in `children`.
+    // Later parents share the node but aren't recorded here.
+    parent: Option<ParentEdge<G>>,
+    // `None` until the node has been expanded.
+    children: Option<Vec<ChildEdge<G>>>,
 }
 
-impl Tree {
+impl<G: Game> Tree<G> {
+
+    pub fn from_board(board: G) -> Self {
+        Tree::lazy(board).expanded()
+    }
+
+    pub fn from_board_up_to_symmetry(board: G) -> Self {
+        Tree::lazy(board).sharing_symmetries().expanded()
+    }
+
+    pub fn lazy(board: G) -> Self {
+        let mut index = HashMap::new();
+        index.insert(board, 0);
+        Tree {
+            nodes: RefCell::new(vec![Some(NodeData { board, depth: 0, parent: None, children: None })]),
+            generations: RefCell::new(vec![0]),
+            index: RefCell::new(index),
+            free: RefCell::new(Vec::new()),
+            root: 0,
+            root_symmetry: G::Symmetry::identity(),
+            root_depth: 0,
+            up_to_symmetry: false,
+            max_depth: None,
+        }
+    }
+
+    pub fn sharing_symmetries(mut self) -> Self {
+        let root_board = self.board_of(self.root);
+        self.up_to_symmetry = true;
+        self.index.replace(HashMap::from([(self.index_key(root_board), self.root)]));
+        self
+    }
+
+    // Positions `max_depth` moves below the root are never expanded, so they look like leaves.
+    // The limit moves down along with the root when it's advanced.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    fn expanded(self) -> Self {
+        let mut seen = HashSet::new();
+        self.expand_all(self.root, &mut seen);
+        self
+    }
+
+    fn expand_all(&self, id: usize, seen: &mut HashSet<usize>) {
+        if !seen.insert(id) {
+            return;
+        }
+        for (_, child_id, _) in self.children_of(id) {
+            self.expand_all(child_id, seen);
+        }
+    }
+
+    fn board_of(&self, id: usize) -> G {
+        self.nodes.borrow()[id].as_ref().expect("Node has been dropped from the tree.").board
+    }
+
+    fn children_of(&self, id: usize) -> Vec<ChildEdge<G>> {
+        let (board, depth) = match &self.nodes.borrow()[id] {
+            Some(NodeData { children: Some(children), .. }) => return children.clone(),
+            Some(data) => (data.board, data.depth),
+            None => panic!("Node has been dropped from the tree."),
+        };
+
+        // Nodes on the depth limit aren't marked as expanded, so they can still grow if the root moves closer to them.
+        if self.max_depth.is_some_and(|max_depth| depth >= self.root_depth + max_depth) {
+            return Vec::new();
+        }
+        let mut children = Vec::new();
+        if board.get_outcome() == Outcome::InProgress {
+            for m in board.get_legal_moves() {
+                let child_board = board.apply(m).expect("Move is legal by definition of get_legal_moves().");
+                let child_id = self.find_or_insert(child_board, depth + 1, (id, m));
+                let symmetry = Tree::<G>::symmetry_between(self.board_of(child_id), child_board);
+                children.push((m, child_id, symmetry));
+            }
+        }
+        self.nodes.borrow_mut()[id].as_mut().unwrap().children = Some(children.clone());
+        children
+    }
+
+    fn find_or_insert(&self, board: G, depth: usize, (parent_id, m): (usize, G::Move)) -> usize {
+        let key = self.index_key(board);
+        if let Some(id) = self.index.borrow().get(&key) {
+            return *id;
+        }
+        // A newly stored node holds exactly the board reached, so the edge from its first parent needs no symmetry.
+        let data = Some(NodeData { board, depth, parent: Some((parent_id, m, G::Symmetry::identity())), children: None });
+        let mut nodes = self.nodes.borrow_mut();
+        let id = match self.free.borrow_mut().pop() {
+            Some(id) => {
+                nodes[id] = data;
+                id
+            },
+            None => {
+                nodes.push(data);
+                self.generations.borrow_mut().push(0);
+                nodes.len() - 1
+            },
+        };
+        self.index.borrow_mut().insert(key, id);
+        id
+    }
+
+    fn index_key(&self, board: G) -> G {
+        match self.up_to_symmetry {
+            true => board.canonical().0,
+            false => board,
+        }
+    }
 
-    pub fn from_board(board: Board) -> Self {
-        let root = Node::from_board(board);
-        Tree { root }
+    // The symmetry taking `stored` onto `actual`, given that the two boards are equivalent.
+    fn symmetry_between(stored: G, actual: G) -> G::Symmetry {
+        if stored == actual {
+            return G::Symmetry::identity();
+        }
+        let (_, from_stored) = stored.canonical();
+        let (_, from_actual) = actual.canonical();
+        from_actual.inverse().compose(&from_stored)
     }
 
+    // Walks the whole tree, so only the tests use it to check its shape.
+    #[cfg(test)]
     pub fn depth(&self) -> usize {
-        self.root.get_max_depth()
+        self.get_root().get_max_depth()
+    }
+
+    pub fn get_root(&self) -> Node<'_, G> {
+        Node { tree: self, id: self.root, symmetry: self.root_symmetry }
+    }
+
+    // The node for `board`, if it has already been generated.
+    pub fn find(&self, board: G) -> Option<Node<'_, G>> {
+        let id = *self.index.borrow().get(&self.index_key(board))?;
+        let symmetry = Tree::<G>::symmetry_between(self.board_of(id), board);
+        Some(Node { tree: self, id, symmetry })
+    }
+
+    // Like `find`, but generates the tree breadth first from the root until `board` turns up, so that a lazy tree
+    // can answer for positions it hasn't reached yet. `None` if it isn't reachable from the root at all.
+    pub fn find_or_expand(&self, board: G) -> Option<Node<'_, G>> {
+        let mut queue = VecDeque::from([self.root]);
+        let mut seen = HashSet::new();
+        while self.find(board).is_none() {
+            let id = queue.pop_front()?;
+            if seen.insert(id) {
+                queue.extend(self.children_of(id).into_iter().map(|(_, child_id, _)| child_id));
+            }
+        }
+        self.find(board)
+    }
+
+    pub fn root_id(&self) -> NodeId {
+        self.node_id(self.root)
     }
 
-    pub fn get_root(&self) -> &Node {
-        &self.root
+    pub fn pre_order(&self) -> PreOrder<'_, G> {
+        self.get_root().pre_order()
     }
 
+    pub fn post_order(&self) -> PostOrder<'_, G> {
+        self.get_root().post_order()
+    }
+
+    pub fn breadth_first(&self) -> BreadthFirst<'_, G> {
+        self.get_root().breadth_first()
+    }
+
+    pub fn walk(&self, visitor: &mut impl Visitor<G>) {
+        self.get_root().walk(visitor);
+    }
+
+    fn node_id(&self, index: usize) -> NodeId {
+        NodeId { index, generation: self.generations.borrow()[index] }
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        id.index < self.generations.borrow().len()
+            && self.generations.borrow()[id.index] == id.generation
+            && self.nodes.borrow()[id.index].is_some()
+    }
+
+    fn check(&self, id: NodeId) -> usize {
+        if !self.contains(id) {
+            panic!("Node has been dropped from the tree.");
+        }
+        id.index
+    }
+
+    // The node as reached along the path recorded by its parent links.
+    pub fn get_node(&self, id: NodeId) -> Node<'_, G> {
+        let mut symmetry = self.root_symmetry;
+        for (_, _, edge_symmetry) in self.edges_to(self.check(id)) {
+            symmetry = symmetry.compose(&edge_symmetry);
+        }
+        Node { tree: self, id: id.index, symmetry }
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        let parent = self.nodes.borrow()[self.check(id)].as_ref().unwrap().parent;
+        parent.map(|(parent_index, _, _)| self.node_id(parent_index))
+    }
+
+    pub fn move_into(&self, id: NodeId) -> Option<G::Move> {
+        self.path_to(id).last().copied()
+    }
+
+    // The moves that lead from the root to this node, following parent links.
+    pub fn path_to(&self, id: NodeId) -> Vec<G::Move> {
+        let mut symmetry = self.root_symmetry;
+        let mut path = Vec::new();
+        for (_, m, edge_symmetry) in self.edges_to(self.check(id)) {
+            path.push(symmetry.transform_move(m));
+            symmetry = symmetry.compose(&edge_symmetry);
+        }
+        path
+    }
+
+    // The parent links from the root down to this node, each as (parent, move, symmetry) in the parent's stored frame.
+    fn edges_to(&self, index: usize) -> Vec<ParentEdge<G>> {
+        let nodes = self.nodes.borrow();
+        let mut edges = Vec::new();
+        let mut current = index;
+        while let Some(edge) = nodes[current].as_ref().unwrap().parent {
+            edges.push(edge);
+            current = edge.0;
+        }
+        edges.reverse();
+        edges
+    }
+
+    pub fn n_positions(&self) -> usize {
+        self.nodes.borrow().len() - self.free.borrow().len()
+    }
+
+    // Every position generated so far. When symmetries are shared, that's one board for each symmetry class.
+    pub fn get_positions(&self) -> Vec<G> {
+        self.nodes.borrow().iter().flatten().map(|data| data.board).collect()
+    }
+
+    // Forgets the children of the position reached by `line`, releasing every position that is no longer reachable.
+    // They are regenerated if they're asked for again.
+    pub fn collapse(&mut self, line: &[G::Move]) -> Result<(), String> {
+        let mut node = self.get_root();
+        for m in line.iter() {
+            node = node.get_child_by_move(*m)?;
+        }
+        let id = node.id;
+        self.nodes.get_mut()[id].as_mut().unwrap().children = None;
+        self.release_unreachable();
+        Ok(())
+    }
+
+    // Makes the child reached by `m` the new root, releasing every position that can no longer be reached from it.
+    pub fn advance(&mut self, m: G::Move) -> Result<(), String> {
+        let child = self.get_root().get_child_by_move(m)?;
+        let (id, symmetry) = (child.id, child.symmetry);
+        self.root = id;
+        self.root_symmetry = symmetry;
+        self.root_depth += 1;
+        self.release_unreachable();
+        self.nodes.get_mut()[id].as_mut().unwrap().parent = None;
+        Ok(())
+    }
+
+    fn release_unreachable(&mut self) {
+        let nodes = self.nodes.get_mut();
+        let mut reachable = vec![false; nodes.len()];
+        // The first edge found into each node, in case its recorded parent no longer leads to it.
+        let mut found_from: Vec<Option<ParentEdge<G>>> = vec![None; nodes.len()];
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if reachable[id] {
+                continue;
+            }
+            reachable[id] = true;
+            if let Some(children) = &nodes[id].as_ref().unwrap().children {
+                for (m, child_id, symmetry) in children.iter() {
+                    found_from[*child_id].get_or_insert((id, *m, *symmetry));
+                    stack.push(*child_id);
+                }
+            }
+        }
+        for id in 0..nodes.len() {
+            if !reachable[id] {
+                if let Some(data) = nodes[id].take() {
+                    let key = match self.up_to_symmetry {
+                        true => data.board.canonical().0,
+                        false => data.board,
+                    };
+                    self.index.get_mut().remove(&key);
+                    self.free.get_mut().push(id);
+                    self.generations.get_mut()[id] += 1;
+                }
+            }
+        }
+        for id in 0..nodes.len() {
+            let parent_is_intact = match nodes[id].as_ref().and_then(|data| data.parent) {
+                Some((parent_id, _, _)) => nodes[parent_id].as_ref().is_some_and(|parent| parent.children.is_some()),
+                None => true,
+            };
+            if !parent_is_intact {
+                nodes[id].as_mut().unwrap().parent = found_from[id];
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn get_max_depth_of(&self, id: usize, depths: &mut HashMap<usize, usize>) -> usize {
+        if let Some(depth) = depths.get(&id) {
+            return *depth;
+        }
+        let depth = 1 + self.children_of(id).iter()
+            .map(|(_, child_id, _)| self.get_max_depth_of(*child_id, depths))
+            .max()
+            .unwrap_or(0);
+        depths.insert(id, depth);
+        depth
+    }
+
+    // Counts every node of the tree below `id` as if transpositions weren't shared.
+    fn get_subtree_size_of(&self, id: usize, sizes: &mut HashMap<usize, usize>) -> usize {
+        if let Some(size) = sizes.get(&id) {
+            return *size;
+        }
+        let size = 1 + self.children_of(id).iter()
+            .map(|(_, child_id, _)| self.get_subtree_size_of(*child_id, sizes))
+            .sum::<usize>();
+        sizes.insert(id, size);
+        size
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: usize,
+    generation: u32,
 }
 
-pub struct Node {
-    board: Board,
-    children: Vec<Node>,
+// A view onto a shared position, as reached from the root. The symmetry maps the stored board onto the board actually on the board.
+#[derive(Clone, Copy)]
+pub struct Node<'a, G: Game = Board> {
+    tree: &'a Tree<G>,
+    id: usize,
+    symmetry: G::Symmetry,
 }
 
-impl Node {
+impl<'a, G: Game> Node<'a, G> {
 
-    pub fn from_board(board: Board) -> Self {
-        let children = match board.get_outcome() {
-            Outcome::InProgress => {
-                let player = board.get_active_player().expect("We've already checked that the game isn't already over.");
-                let mut children = Vec::new();
-                for row in 0..3 {
-                    for col in 0..3 {
-                        if let Ok(child_board) = board.with_move_made(player, Move::new(row, col)) {
-                            children.push(Node::from_board(child_board));
-                        }
-                    }
-                }
-                children
-            },
-            _ => Vec::new(),
-        };
-        Node { board, children }
+    pub fn get_id(&self) -> NodeId {
+        self.tree.node_id(self.id)
     }
 
-    pub fn get_board(&self) -> Board {
-        self.board
+    pub fn get_board(&self) -> G {
+        self.symmetry.transform(&self.tree.board_of(self.id))
     }
 
-    pub fn get_legal_moves(&self) -> Vec<Move> {
-        self.board.get_legal_moves()
+    pub fn get_legal_moves(&self) -> Vec<G::Move> {
+        self.get_board().get_legal_moves()
     }
 
     pub fn get_active_player(&self) -> Option<Player> {
-        self.board.get_active_player()
+        self.get_board().get_active_player()
     }
 
     pub fn n_children(&self) -> usize {
-        self.children.len()
+        self.tree.children_of(self.id).len()
     }
 
+    #[cfg(test)]
     pub fn get_max_depth(&self) -> usize {
-        if self.n_children() == 0 {
-            return 1;
-        } else {
-            return 1 + self.children.iter().map(|child| child.get_max_depth()).max().unwrap();
-        }
+        self.tree.get_max_depth_of(self.id, &mut HashMap::new())
+    }
+
+    pub fn get_subtree_size(&self) -> usize {
+        self.tree.get_subtree_size_of(self.id, &mut HashMap::new())
     }
 
-    pub fn get_children(&self) -> &Vec<Node> {
-        &self.children
+    pub fn get_children(&self) -> Vec<Node<'a, G>> {
+        let mut children = self.tree.children_of(self.id).iter()
+            .map(|(m, child_id, child_symmetry)| (
+                self.symmetry.transform_move(*m),
+                Node { tree: self.tree, id: *child_id, symmetry: self.symmetry.compose(child_symmetry) },
+            ))
+            .collect::<Vec<(G::Move, Node<'a, G>)>>();
+        children.sort_by_key(|(m, _)| *m);
+        children.into_iter().map(|(_, child)| child).collect()
     }
 
-    pub fn get_child(&self, row: usize, col: usize) -> Result<&Node, String> {
-        if self.board.get_outcome() != Outcome::InProgress {
+    pub fn get_child_by_move(&self, m: G::Move) -> Result<Node<'a, G>, String> {
+        if self.get_outcome() != Outcome::InProgress {
             return Err("Game is already over".to_string());
         }
-        let player = self.board.get_active_player().expect("We've already checked that the game isn't already over.");
-        self.children.iter()
-            .find(|child| Ok(child.board) == self.board.with_move_made(player, Move::new(row, col)))
+        let stored_move = self.symmetry.inverse().transform_move(m);
+        self.tree.children_of(self.id).iter()
+            .find(|(child_move, _, _)| *child_move == stored_move)
             .map_or(
-                Err(format!("There is no child with the move {}", Move::new(row, col).to_string())),
-                |x| Ok(x)
+                Err(format!("There is no child with the move {:?}", m)),
+                |(_, child_id, child_symmetry)| Ok(Node { tree: self.tree, id: *child_id, symmetry: self.symmetry.compose(child_symmetry) })
             )
     }
 
     pub fn get_outcome(&self) -> Outcome {
-        self.board.get_outcome()
+        self.tree.board_of(self.id).get_outcome()
+    }
+
+    pub fn pre_order(&self) -> PreOrder<'a, G> {
+        PreOrder { stack: vec![Visit::start(*self)] }
+    }
+
+    pub fn post_order(&self) -> PostOrder<'a, G> {
+        PostOrder { stack: vec![(Visit::start(*self), false)] }
     }
 
+    pub fn breadth_first(&self) -> BreadthFirst<'a, G> {
+        BreadthFirst { queue: VecDeque::from([Visit::start(*self)]) }
+    }
+
+    pub fn walk(&self, visitor: &mut impl Visitor<G>) {
+        Visit::start(*self).walk(visitor);
+    }
+
+}
+
+impl<'a> Node<'a, Board> {
+
+    pub fn get_child(&self, row: usize, col: usize) -> Result<Node<'a>, String> {
+        self.get_child_by_move(Move::new(row, col)).map_err(|error| match self.get_outcome() {
+            Outcome::InProgress => format!("There is no child with the move {}", Move::new(row, col).to_string()),
+            _ => error,
+        })
+    }
+
+}
+
+// The traversals below follow every edge, so a shared position is visited once for each path that reaches it.
+// Depths and paths are relative to the node the traversal started from.
+#[derive(Clone)]
+pub struct Visit<'a, G: Game = Board> {
+    pub node: Node<'a, G>,
+    pub depth: usize,
+    pub path: Vec<G::Move>,
+}
+
+impl<'a, G: Game> Visit<'a, G> {
+
+    fn start(node: Node<'a, G>) -> Self {
+        Visit { node, depth: 0, path: Vec::new() }
+    }
+
+    // The visits to each child, in the order of their moves.
+    pub fn children(&self) -> Vec<Visit<'a, G>> {
+        let mut moves = self.node.get_legal_moves();
+        moves.sort();
+        moves.into_iter()
+            .zip(self.node.get_children())
+            .map(|(m, child)| {
+                let mut path = self.path.clone();
+                path.push(m);
+                Visit { node: child, depth: self.depth + 1, path }
+            })
+            .collect()
+    }
+
+    fn walk(&self, visitor: &mut impl Visitor<G>) {
+        if visitor.enter(self) == VisitAction::Continue {
+            for child in self.children() {
+                child.walk(visitor);
+            }
+        }
+        visitor.leave(self);
+    }
+
+}
+
+pub struct PreOrder<'a, G: Game = Board> {
+    stack: Vec<Visit<'a, G>>,
+}
+
+impl<'a, G: Game> Iterator for PreOrder<'a, G> {
+    type Item = Visit<'a, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.stack.pop()?;
+        self.stack.extend(visit.children().into_iter().rev());
+        Some(visit)
+    }
+}
+
+pub struct PostOrder<'a, G: Game = Board> {
+    // Each visit is yielded the second time it's popped, once its children have been.
+    stack: Vec<(Visit<'a, G>, bool)>,
+}
+
+impl<'a, G: Game> Iterator for PostOrder<'a, G> {
+    type Item = Visit<'a, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (visit, children_done) = self.stack.pop()?;
+            if children_done {
+                return Some(visit);
+            }
+            let children = visit.children();
+            self.stack.push((visit, true));
+            self.stack.extend(children.into_iter().rev().map(|child| (child, false)));
+        }
+    }
+}
+
+pub struct BreadthFirst<'a, G: Game = Board> {
+    queue: VecDeque<Visit<'a, G>>,
+}
+
+impl<'a, G: Game> Iterator for BreadthFirst<'a, G> {
+    type Item = Visit<'a, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.queue.pop_front()?;
+        self.queue.extend(visit.children());
+        Some(visit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitAction {
+    Continue,
+    // Skip the node's children. `leave` is still called for the node itself.
+    Prune,
+}
+
+pub trait Visitor<G: Game = Board> {
+
+    fn enter(&mut self, _visit: &Visit<G>) -> VisitAction {
+        VisitAction::Continue
+    }
+
+    fn leave(&mut self, _visit: &Visit<G>) {}
+
 }
 
 
@@ -205,7 +692,271 @@ mod test_tree {
             10
         );
 
+        assert_eq!(
+            tree.get_root().get_subtree_size(),
+            549946
+        );
+
+    }
+
+    #[test]
+    fn test_tree_shares_transpositions() {
+        let tree = Tree::from_board(
+            Board::empty()
+        );
+        assert_eq!(tree.n_positions(), 5478);
+
+        let via_corner_first = tree.get_root().get_child(0, 0).unwrap().get_child(1, 1).unwrap().get_child(2, 2).unwrap();
+        let via_far_corner_first = tree.get_root().get_child(2, 2).unwrap().get_child(1, 1).unwrap().get_child(0, 0).unwrap();
+        assert_eq!(via_corner_first.get_board(), via_far_corner_first.get_board());
+        assert_eq!(via_corner_first.n_children(), 6);
+    }
+
+    #[test]
+    fn test_tree_shares_symmetric_positions() {
+        let tree = Tree::from_board_up_to_symmetry(
+            Board::empty()
+        );
+        assert_eq!(tree.n_positions(), 765);
+        assert_eq!(tree.depth(), 10);
+
+        let board = Board::from_position(
+            "___
+            ___
+            __X",
+        ).unwrap();
+        let child = tree.get_root().get_child(2, 2).unwrap();
+        assert_eq!(child.get_board(), board);
+
+        let grandchild = child.get_child(0, 1).unwrap();
+        assert_eq!(
+            grandchild.get_board(),
+            Board::from_position(
+                "_O_
+                ___
+                __X",
+            ).unwrap()
+        );
+        assert_eq!(
+            grandchild.get_children().iter().map(|node| node.get_board()).collect::<Vec<Board>>(),
+            grandchild.get_legal_moves().iter().map(|m| grandchild.get_board().with_move_made(Player::X, *m).unwrap()).collect::<Vec<Board>>()
+        );
+    }
+
+    #[test]
+    fn test_lazy_tree_expands_on_demand() {
+        let tree = Tree::lazy(Board::empty());
+        assert_eq!(tree.n_positions(), 1);
+
+        let child = tree.get_root().get_child(1, 1).unwrap();
+        assert_eq!(tree.n_positions(), 10);
+
+        assert_eq!(child.n_children(), 8);
+        assert_eq!(tree.n_positions(), 18);
+
+        let board = Board::from_position("XO__X____").unwrap();
+        assert!(tree.find(board).is_none());
+        assert_eq!(tree.find_or_expand(board).unwrap().get_board(), board);
+        assert!(tree.find_or_expand(Board::from_position("XX_______").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_depth_limited_tree() {
+        let mut tree = Tree::lazy(Board::empty()).with_max_depth(2);
+        assert_eq!(tree.depth(), 3);
+        assert_eq!(tree.n_positions(), 82);
+        assert_eq!(tree.get_root().get_child(0, 0).unwrap().get_child(1, 1).unwrap().n_children(), 0);
+
+        tree.collapse(&[Move::new(0, 0)]).unwrap();
+        assert_eq!(tree.n_positions(), 74);
+        assert_eq!(tree.get_root().get_child(0, 0).unwrap().n_children(), 8);
+        assert_eq!(tree.n_positions(), 82);
+
+        tree.collapse(&[]).unwrap();
+        assert_eq!(tree.n_positions(), 1);
+        assert_eq!(tree.depth(), 3);
+    }
+
+    #[test]
+    fn test_tree_walks_up_from_node_ids() {
+        let tree = Tree::from_board(
+            Board::empty()
+        );
+        let root_id = tree.root_id();
+        assert_eq!(tree.parent(root_id), None);
+        assert_eq!(tree.move_into(root_id), None);
+        assert_eq!(tree.path_to(root_id), vec![]);
+
+        let node = tree.get_root().get_child(0, 0).unwrap().get_child(1, 1).unwrap();
+        let id = node.get_id();
+        assert_eq!(tree.path_to(id), vec![Move::new(0, 0), Move::new(1, 1)]);
+        assert_eq!(tree.move_into(id), Some(Move::new(1, 1)));
+        assert_eq!(tree.get_node(tree.parent(id).unwrap()).get_board(), tree.get_root().get_child(0, 0).unwrap().get_board());
+        assert_eq!(tree.get_node(id).get_board(), node.get_board());
+
+        // Transpositions keep the path they were first reached by.
+        let transposed = tree.get_root().get_child(2, 2).unwrap().get_child(1, 1).unwrap().get_child(0, 0).unwrap();
+        assert_eq!(tree.path_to(transposed.get_id()), vec![Move::new(0, 0), Move::new(1, 1), Move::new(2, 2)]);
+    }
+
+    #[test]
+    fn test_symmetric_tree_walks_up_from_node_ids() {
+        let tree = Tree::from_board_up_to_symmetry(
+            Board::empty()
+        );
+        let node = tree.get_root().get_child(2, 2).unwrap().get_child(0, 1).unwrap();
+        let path = tree.path_to(node.get_id());
+        let mut board = Board::empty();
+        for m in path.iter() {
+            board = board.with_move_made(board.get_active_player().unwrap(), *m).unwrap();
+        }
+        assert_eq!(board, tree.get_node(node.get_id()).get_board());
+        assert_eq!(board.canonical().0, node.get_board().canonical().0);
+    }
+
+    #[test]
+    fn test_node_ids_survive_collapsing() {
+        let mut tree = Tree::lazy(Board::empty()).with_max_depth(2);
+        tree.depth();
+        let kept = tree.get_root().get_child(1, 1).unwrap().get_child(0, 0).unwrap().get_id();
+        let dropped = tree.get_root().get_child(0, 0).unwrap().get_child(1, 1).unwrap().get_id();
+
+        tree.collapse(&[Move::new(0, 0)]).unwrap();
+        assert!(tree.contains(kept));
+        assert!(!tree.contains(dropped));
+        assert_eq!(tree.path_to(kept), vec![Move::new(1, 1), Move::new(0, 0)]);
+
+        tree.get_root().get_child(0, 0).unwrap().get_child(1, 1).unwrap();
+        assert!(!tree.contains(dropped));
+    }
+
+    #[test]
+    fn test_node_ids_are_reparented_when_their_parent_is_dropped() {
+        let mut tree = Tree::lazy(Board::empty());
+        let id = tree.get_root().get_child(0, 0).unwrap().get_child(1, 1).unwrap().get_child(2, 2).unwrap().get_id();
+        tree.get_root().get_child(2, 2).unwrap().get_child(1, 1).unwrap().get_child(0, 0).unwrap();
+
+        tree.collapse(&[Move::new(0, 0)]).unwrap();
+        assert!(tree.contains(id));
+        assert_eq!(tree.path_to(id), vec![Move::new(2, 2), Move::new(1, 1), Move::new(0, 0)]);
     }
 
+    #[test]
+    fn test_tree_traversal_orders() {
+        let tree = Tree::from_board(
+            Board::from_position(
+                "XOX
+                O__
+                XOX",
+            ).unwrap()
+        );
 
+        let pre_order = tree.pre_order().map(|visit| (visit.depth, visit.path)).collect::<Vec<(usize, Vec<Move>)>>();
+        assert_eq!(pre_order, vec![
+            (0, vec![]),
+            (1, vec![Move::new(1, 1)]),
+            (1, vec![Move::new(1, 2)]),
+            (2, vec![Move::new(1, 2), Move::new(1, 1)]),
+        ]);
+
+        let post_order = tree.post_order().map(|visit| visit.path).collect::<Vec<Vec<Move>>>();
+        assert_eq!(post_order, vec![
+            vec![Move::new(1, 1)],
+            vec![Move::new(1, 2), Move::new(1, 1)],
+            vec![Move::new(1, 2)],
+            vec![],
+        ]);
+
+        let breadth_first = tree.breadth_first().map(|visit| visit.node.get_board()).collect::<Vec<Board>>();
+        assert_eq!(breadth_first, vec![
+            Board::from_position("XOXO__XOX").unwrap(),
+            Board::from_position("XOXOO_XOX").unwrap(),
+            Board::from_position("XOXO_OXOX").unwrap(),
+            Board::from_position("XOXOXOXOX").unwrap(),
+        ]);
+
+        assert_eq!(Tree::from_board(Board::empty()).pre_order().count(), 549946);
+    }
+
+    struct FinishedGames {
+        max_depth: usize,
+        finished: usize,
+        entered: usize,
+        left: usize,
+    }
+
+    impl Visitor for FinishedGames {
+
+        fn enter(&mut self, visit: &Visit) -> VisitAction {
+            self.entered += 1;
+            if visit.node.get_outcome() != Outcome::InProgress {
+                self.finished += 1;
+            }
+            match visit.depth < self.max_depth {
+                true => VisitAction::Continue,
+                false => VisitAction::Prune,
+            }
+        }
+
+        fn leave(&mut self, _visit: &Visit) {
+            self.left += 1;
+        }
+
+    }
+
+    #[test]
+    fn test_tree_visitor_prunes_subtrees() {
+        let tree = Tree::lazy(Board::empty());
+        let mut visitor = FinishedGames { max_depth: 5, finished: 0, entered: 0, left: 0 };
+        tree.walk(&mut visitor);
+        assert_eq!(visitor.finished, 1440);
+        assert_eq!(visitor.entered, 1 + 9 + 72 + 504 + 3024 + 15120);
+        assert_eq!(visitor.left, visitor.entered);
+        // Pruned nodes aren't expanded, so nothing more than five moves in gets generated.
+        assert_eq!(tree.n_positions(), 1 + 9 + 72 + 252 + 756 + 1260);
+    }
+
+    #[test]
+    fn test_tree_advances_root() {
+        let mut tree = Tree::from_board(Board::empty());
+        let kept = tree.get_root().get_child(1, 1).unwrap().get_child(0, 0).unwrap().get_id();
+        let dropped = tree.get_root().get_child(0, 0).unwrap().get_id();
+
+        tree.advance(Move::new(1, 1)).unwrap();
+        assert_eq!(tree.get_root().get_board(), Board::from_position("____X____").unwrap());
+        assert_eq!(tree.depth(), 9);
+        assert_eq!(tree.n_positions(), tree.get_root().pre_order().map(|visit| visit.node.get_board()).collect::<HashSet<Board>>().len());
+        assert!(tree.contains(kept));
+        assert!(!tree.contains(dropped));
+        assert_eq!(tree.parent(tree.root_id()), None);
+        assert_eq!(tree.path_to(kept), vec![Move::new(0, 0)]);
+        assert!(tree.advance(Move::new(1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_tree_advances_root_into_shared_nodes() {
+        let mut tree = Tree::lazy(Board::empty()).sharing_symmetries().with_max_depth(1);
+        tree.advance(Move::new(2, 2)).unwrap();
+        assert_eq!(
+            tree.get_root().get_board(),
+            Board::from_position(
+                "___
+                ___
+                __X",
+            ).unwrap()
+        );
+        assert_eq!(tree.depth(), 2);
+
+        tree.advance(Move::new(0, 1)).unwrap();
+        assert_eq!(
+            tree.get_root().get_board(),
+            Board::from_position(
+                "_O_
+                ___
+                __X",
+            ).unwrap()
+        );
+        assert_eq!(tree.get_root().n_children(), 7);
+        assert_eq!(tree.path_to(tree.get_root().get_child(1, 1).unwrap().get_id()), vec![Move::new(1, 1)]);
+    }
 }
\ No newline at end of file